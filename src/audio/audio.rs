@@ -30,7 +30,7 @@ pub struct Sounds {
 }
 
 pub fn play_bounce_sound(settings: &AudioSettings, sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
-    if let Some(ref output) = output.as_ref() {
+    if let Some(output) = output {
         if let Some(sound) = storage.get(&sounds.bounce_sfx) {
             output.play_once(sound, settings.effects_volume)
         }
@@ -38,7 +38,7 @@ pub fn play_bounce_sound(settings: &AudioSettings, sounds: &Sounds, storage: &As
 }
 
 pub fn play_score_sound(settings: &AudioSettings, sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
-    if let Some(ref output) = output.as_ref() {
+    if let Some(output) = output {
         if let Some(sound) = storage.get(&sounds.score_sfx) {
             output.play_once(sound, settings.effects_volume);
         }
@@ -48,6 +48,14 @@ pub fn play_score_sound(settings: &AudioSettings, sounds: &Sounds, storage: &Ass
     }
 }
 
+pub fn play_point_sound(settings: &AudioSettings, sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
+    if let Some(output) = output {
+        if let Some(sound) = storage.get(&sounds.score_sfx) {
+            output.play_once(sound, settings.effects_volume);
+        }
+    }
+}
+
 /// Loads an ogg audio track.
 fn load_audio_track(loader: &Loader, world: &World, file: &str) -> SourceHandle {
     loader.load(file, OggFormat, (), &world.read_resource())
@@ -61,16 +69,16 @@ pub fn initialise_audio(world: &mut World, settings: &Settings) {
 
         let music = MUSIC_TRACKS
             .iter()
-            .map(|file| load_audio_track(&loader, &world, file))
+            .map(|file| load_audio_track(&loader, world, file))
             .collect::<Vec<_>>()
             .into_iter()
             .cycle();
         let music = Music { music };
 
         let sound = Sounds {
-            bounce_sfx: load_audio_track(&loader, &world, BOUNCE_SOUND),
-            score_sfx: load_audio_track(&loader, &world, SCORE_SOUND),
-            boo_sfx: load_audio_track(&loader, &world, BOO_SOUND),
+            bounce_sfx: load_audio_track(&loader, world, BOUNCE_SOUND),
+            score_sfx: load_audio_track(&loader, world, SCORE_SOUND),
+            boo_sfx: load_audio_track(&loader, world, BOO_SOUND),
         };
 
         (sound, music)
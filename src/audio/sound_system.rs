@@ -0,0 +1,51 @@
+use amethyst::assets::AssetStorage;
+use amethyst::audio::output::Output;
+use amethyst::audio::Source;
+use amethyst::core::ecs::{Read, ReadExpect, System, SystemData, World};
+use amethyst::core::SystemDesc;
+use amethyst::shrev::{EventChannel, ReaderId};
+
+use crate::audio::audio::{play_bounce_sound, play_point_sound, play_score_sound, Sounds};
+use crate::events::GameEvent;
+use crate::persistence::Settings;
+use crate::simulation::paddle::Side;
+
+/// Plays the sounds of paddle hits, goals and power-ups.
+pub struct SoundSystem {
+    reader: ReaderId<GameEvent>,
+}
+
+#[derive(Default, Debug)]
+pub struct SoundSystemDesc;
+
+impl<'a, 'b> SystemDesc<'a, 'b, SoundSystem> for SoundSystemDesc {
+    fn build(self, world: &mut World) -> SoundSystem {
+        <SoundSystem as System<'_>>::SystemData::setup(world);
+        let reader = world.fetch_mut::<EventChannel<GameEvent>>().register_reader();
+        SoundSystem { reader }
+    }
+}
+
+impl<'s> System<'s> for SoundSystem {
+    type SystemData = (
+        Read<'s, EventChannel<GameEvent>>,
+        Read<'s, AssetStorage<Source>>,
+        ReadExpect<'s, Sounds>,
+        Option<Read<'s, Output>>,
+        Read<'s, Settings>,
+    );
+
+    fn run(&mut self, (events, storage, sounds, audio_output, settings): Self::SystemData) {
+        let audio_settings = &settings.audio_settings;
+        for event in events.read(&mut self.reader) {
+            match event {
+                GameEvent::PaddleHit { .. } | GameEvent::PowerUp { .. } => play_bounce_sound(audio_settings, &sounds, &storage, audio_output.as_deref()),
+                // Computer scores.
+                GameEvent::Goal { side: Side::Left } => play_score_sound(audio_settings, &sounds, &storage, audio_output.as_deref()),
+                // Player scores.
+                GameEvent::Goal { side: Side::Right } => play_point_sound(audio_settings, &sounds, &storage, audio_output.as_deref()),
+                _ => {}
+            }
+        }
+    }
+}
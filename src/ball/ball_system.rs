@@ -1,11 +1,12 @@
 use amethyst::{
     core::timing::Time,
-    core::transform::Transform,
     derive::SystemDesc,
-    ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
+    ecs::{Read, System, SystemData, Write},
 };
+use amethyst::shrev::EventChannel;
 
-use crate::ball::component::Ball;
+use crate::events::{publish, GameEvent};
+use crate::simulation::Simulation;
 use crate::state::Pause;
 
 #[derive(SystemDesc)]
@@ -13,20 +14,17 @@ pub struct MoveBallsSystem;
 
 impl<'s> System<'s> for MoveBallsSystem {
     type SystemData = (
-        ReadStorage<'s, Ball>,
-        WriteStorage<'s, Transform>,
+        Write<'s, Simulation>,
         Read<'s, Time>,
         Read<'s, Pause>,
+        Write<'s, EventChannel<GameEvent>>,
     );
 
-    fn run(&mut self, (balls, mut locals, time, pause): Self::SystemData) {
-        // Move every ball according to its speed, and the time passed.
+    fn run(&mut self, (mut simulation, time, pause, mut game_events): Self::SystemData) {
         if pause.paused {
             return;
         }
-        for (ball, local) in (&balls, &mut locals).join() {
-            local.prepend_translation_x(ball.velocity[0] * time.delta_seconds());
-            local.prepend_translation_y(ball.velocity[1] * time.delta_seconds());
-        }
+        publish(&mut game_events, simulation.advance_clock(time.fixed_seconds()));
+        simulation.move_balls(time.fixed_seconds());
     }
 }
@@ -1,9 +1,10 @@
 use amethyst::core::ecs::{Component, DenseVecStorage};
 
+use crate::simulation::ball::BallId;
+
+/// Links an entity to its ball in the `Simulation`.
 pub struct Ball {
-    pub velocity: [f32; 2],
-    pub radius: f32,
-    pub calculated_impact_y: Option<f32>,
+    pub id: BallId,
 }
 
 impl Component for Ball {
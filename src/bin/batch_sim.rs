@@ -0,0 +1,226 @@
+//! Plays rallies between two AIs without a window, and prints statistics about them to tune the
+//! ball speed by numbers rather than by feel.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use impossi_pong::cli::{parse_value, value, CliError};
+use impossi_pong::level::Levels;
+use impossi_pong::persistence::Settings;
+use impossi_pong::simulation::ai::Difficulty;
+use impossi_pong::simulation::arena::ARENA;
+use impossi_pong::simulation::ball::{BallMode, SPEED_INCREMENT};
+use impossi_pong::simulation::controller::Controller;
+use impossi_pong::simulation::paddle::Side;
+use impossi_pong::simulation::power_up::PowerUpRules;
+use impossi_pong::simulation::rng::clock_seed;
+use impossi_pong::simulation::rules::MatchRules;
+use impossi_pong::simulation::{SimEvent, Simulation, SimulationConfig, TickInput};
+
+const USAGE: &str = "\
+Usage: batch_sim [OPTIONS]
+
+Options:
+    --rallies <n>          Number of rallies to play, 1000 by default
+    --left <level>         Difficulty of the left AI: easy, normal, hard or impossible
+    --right <level>        Difficulty of the right AI, both default to the difficulty in the settings
+    --config <file>        Read the fixed step from <file> instead of the saved settings
+    --seed <number>        Seed of the first rally, the next ones count up from it
+    --format <format>      json for a summary, csv for one row per rally, json by default
+    --help                 Print this help";
+
+/// Seconds after which a rally is stopped, such as between two AIs that never miss.
+const RALLY_MAX_SECONDS: f32 = 120.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Options {
+    rallies: u32,
+    left: Option<Difficulty>,
+    right: Option<Difficulty>,
+    config: Option<PathBuf>,
+    seed: Option<u64>,
+    format: Format,
+    help: bool,
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, CliError> {
+        let mut options = Options {
+            rallies: 1000,
+            left: None,
+            right: None,
+            config: None,
+            seed: None,
+            format: Format::Json,
+            help: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--rallies" => options.rallies = parse_value(&mut args, "--rallies", |rallies: &u32| *rallies > 0)?,
+                "--left" => options.left = Some(parse_value(&mut args, "--left", |_: &Difficulty| true)?),
+                "--right" => options.right = Some(parse_value(&mut args, "--right", |_: &Difficulty| true)?),
+                "--config" => options.config = Some(PathBuf::from(value(&mut args, "--config")?)),
+                "--seed" => options.seed = Some(parse_value(&mut args, "--seed", |_: &u64| true)?),
+                "--format" => options.format = parse_value(&mut args, "--format", |_: &Format| true)?,
+                "--help" | "-h" => options.help = true,
+                _ => return Err(CliError::Unknown(arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// How one rally went, from the serve until a goal.
+#[derive(Debug, Copy, Clone)]
+struct Rally {
+    seed: u64,
+    seconds: f32,
+    paddle_hits: u32,
+    wall_bounces: u32,
+    /// Fastest horizontal speed the ball reached, in arena units per second.
+    max_speed: f32,
+    /// The side that won the point, `None` when the rally was stopped.
+    point_for: Option<Side>,
+}
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    if let Some(path) = &options.config {
+        Settings::use_path(path);
+    }
+    let settings = Settings::read_or_default();
+    let left = options.left.unwrap_or(settings.difficulty);
+    let right = options.right.unwrap_or(settings.difficulty);
+    let config = SimulationConfig {
+        // Points are only counted here, the rules should never end the match.
+        match_rules: MatchRules::Survival,
+        controllers: [Controller::Ai(left.profile()), Controller::Ai(right.profile())],
+        seed: options.seed.unwrap_or_else(clock_seed),
+        // A rally ends with the first goal, so extra balls would never get to play.
+        ball_mode: BallMode::Single,
+        // Only the AI is measured, without power-ups getting in the way.
+        power_ups: PowerUpRules::default(),
+        bounce: settings.bounce,
+        level: Levels::read().get(settings.level.as_deref()).clone(),
+    };
+
+    let rallies: Vec<Rally> = (0..options.rallies)
+        .map(|index| play_rally(SimulationConfig { seed: config.seed.wrapping_add(index as u64), ..config.clone() }, settings.physics_settings.fixed_step))
+        .collect();
+
+    match options.format {
+        Format::Json => print_json(&config, left, right, &rallies),
+        Format::Csv => print_csv(&rallies),
+    }
+}
+
+/// Serves a ball in a new match, and follows it until either side scores.
+fn play_rally(config: SimulationConfig, fixed_step: f32) -> Rally {
+    let mut simulation = Simulation::new(config);
+    while simulation.balls().is_empty() {
+        simulation.advance_clock(fixed_step);
+    }
+    let mut rally = Rally {
+        seed: simulation.seed(),
+        seconds: 0.0,
+        paddle_hits: 0,
+        wall_bounces: 0,
+        max_speed: 0.0,
+        point_for: None,
+    };
+    while rally.point_for.is_none() && rally.seconds < RALLY_MAX_SECONDS {
+        rally.max_speed = simulation.balls().iter()
+            .map(|ball| ball.velocity[0].abs())
+            .fold(rally.max_speed, f32::max);
+        for event in simulation.step(fixed_step, &TickInput::default()) {
+            match event {
+                SimEvent::Serve | SimEvent::PowerUp { .. } => {}
+                SimEvent::PaddleHit { .. } => rally.paddle_hits += 1,
+                SimEvent::WallBounce => rally.wall_bounces += 1,
+                SimEvent::Goal { side } => rally.point_for = Some(side.opponent()),
+            }
+        }
+        rally.seconds += fixed_step;
+    }
+    rally
+}
+
+fn print_json(config: &SimulationConfig, left: Difficulty, right: Difficulty, rallies: &[Rally]) {
+    let points = |side: Side| rallies.iter().filter(|rally| rally.point_for == Some(side)).count();
+    let seconds: Vec<f32> = rallies.iter().map(|rally| rally.seconds).collect();
+    let paddle_hits: Vec<f32> = rallies.iter().map(|rally| rally.paddle_hits as f32).collect();
+    let max_speeds: Vec<f32> = rallies.iter().map(|rally| rally.max_speed).collect();
+    println!("{{");
+    println!("  \"seed\": {},", config.seed);
+    println!("  \"left\": \"{}\",", left.name());
+    println!("  \"right\": \"{}\",", right.name());
+    println!("  \"arena\": [{}, {}],", ARENA.width(), ARENA.height());
+    println!("  \"serve_speed\": {},", ARENA.ball_velocity_x());
+    println!("  \"max_velocity\": {},", ARENA.max_velocity());
+    println!("  \"speed_increment\": {},", SPEED_INCREMENT);
+    println!("  \"rallies\": {},", rallies.len());
+    println!("  \"unfinished_rallies\": {},", rallies.iter().filter(|rally| rally.point_for.is_none()).count());
+    println!("  \"points\": {{ \"left\": {}, \"right\": {} }},", points(Side::Left), points(Side::Right));
+    println!("  \"paddle_hits\": {},", rallies.iter().map(|rally| rally.paddle_hits).sum::<u32>());
+    println!("  \"wall_bounces\": {},", rallies.iter().map(|rally| rally.wall_bounces).sum::<u32>());
+    println!("  \"rally_seconds\": {},", distribution_json(seconds));
+    println!("  \"rally_paddle_hits\": {},", distribution_json(paddle_hits));
+    println!("  \"rally_max_speed\": {}", distribution_json(max_speeds));
+    println!("}}");
+}
+
+fn print_csv(rallies: &[Rally]) {
+    println!("rally,seed,seconds,paddle_hits,wall_bounces,max_speed,point_for");
+    for (index, rally) in rallies.iter().enumerate() {
+        let point_for = match rally.point_for {
+            Some(Side::Left) => "left",
+            Some(Side::Right) => "right",
+            None => "",
+        };
+        println!(
+            "{},{},{:.3},{},{},{:.1},{}",
+            index + 1, rally.seed, rally.seconds, rally.paddle_hits, rally.wall_bounces, rally.max_speed, point_for
+        );
+    }
+}
+
+/// The minimum, mean, percentiles and maximum of `values`, as a JSON object.
+fn distribution_json(mut values: Vec<f32>) -> String {
+    values.sort_by(f32::total_cmp);
+    let percentile = |p: f32| values[((values.len() - 1) as f32 * p).round() as usize];
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    format!(
+        "{{ \"min\": {:.3}, \"mean\": {:.3}, \"p10\": {:.3}, \"p50\": {:.3}, \"p90\": {:.3}, \"p99\": {:.3}, \"max\": {:.3} }}",
+        values[0], mean, percentile(0.1), percentile(0.5), percentile(0.9), percentile(0.99), values[values.len() - 1]
+    )
+}
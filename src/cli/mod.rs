@@ -0,0 +1,227 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::persistence::{Settings, WindowMode};
+use crate::level::Levels;
+use crate::power_up;
+use crate::simulation::ai::Difficulty;
+use crate::simulation::controller::Controller;
+use crate::simulation::rules::{MatchResult, MatchRules};
+use crate::simulation::paddle::Side;
+use crate::simulation::{SimEvent, Simulation, SimulationConfig, TickInput};
+
+pub const USAGE: &str = "\
+Usage: impossi-pong [OPTIONS]
+
+Options:
+    --config <file>        Read and save settings in <file> instead of the config directory
+    --width <pixels>       Width of the window, for this run only
+    --height <pixels>      Height of the window, for this run only
+    --fullscreen           Start fullscreen on the monitor picked in the display options, for this run only
+    --difficulty <level>   AI difficulty: easy, normal, hard or impossible, for this run only
+    --seed <number>        Seed of the randomness of every match, for this run only
+    --skip-intro           Start at the main menu instead of the start screen
+    --replay <file>        Start by playing the replay in <file>
+    --headless-sim <n>     Simulate <n> matches without a window, print their results and exit
+    --help                 Print this help
+
+The window size, fullscreen, difficulty and seed are used for this run only and never saved,
+unless changed again in the game.
+Settings and bindings are saved in the platform config directory, or in $IMPOSSI_PONG_CONFIG_DIR when set.";
+
+/// Seconds after which a match simulated by `--headless-sim` is stopped if it didn't end.
+const HEADLESS_MAX_SECONDS: f32 = 600.0;
+
+/// The options the game was launched with, everything left out keeps its value from the settings.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Cli {
+    pub config: Option<PathBuf>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub fullscreen: bool,
+    pub difficulty: Option<Difficulty>,
+    pub seed: Option<u64>,
+    pub skip_intro: bool,
+    pub replay: Option<PathBuf>,
+    pub headless_sim: Option<u32>,
+    pub help: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliError {
+    Unknown(String),
+    MissingValue(&'static str),
+    InvalidValue { option: &'static str, value: String },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Unknown(arg) => write!(f, "Unknown argument {}", arg),
+            CliError::MissingValue(option) => write!(f, "{} needs a value", option),
+            CliError::InvalidValue { option, value } => write!(f, "Invalid value {} for {}", value, option),
+        }
+    }
+}
+
+impl Cli {
+    /// Parses the arguments, without the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Cli, CliError> {
+        let mut cli = Cli::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => cli.config = Some(PathBuf::from(value(&mut args, "--config")?)),
+                "--width" => cli.width = Some(parse_value(&mut args, "--width", |width: &f32| *width > 0.0)?),
+                "--height" => cli.height = Some(parse_value(&mut args, "--height", |height: &f32| *height > 0.0)?),
+                "--fullscreen" => cli.fullscreen = true,
+                "--difficulty" => cli.difficulty = Some(parse_value(&mut args, "--difficulty", |_: &Difficulty| true)?),
+                "--seed" => cli.seed = Some(parse_value(&mut args, "--seed", |_: &u64| true)?),
+                "--skip-intro" => cli.skip_intro = true,
+                "--replay" => cli.replay = Some(PathBuf::from(value(&mut args, "--replay")?)),
+                "--headless-sim" => cli.headless_sim = Some(parse_value(&mut args, "--headless-sim", |_: &u32| true)?),
+                "--help" | "-h" => cli.help = true,
+                _ => return Err(CliError::Unknown(arg)),
+            }
+        }
+        Ok(cli)
+    }
+
+    /// Overrides the settings with the options that were given, for this run only.
+    pub fn apply(&self, settings: &mut Settings) {
+        settings.override_for_run(|settings| {
+            if let Some(width) = self.width {
+                settings.window_settings.width = width;
+            }
+            if let Some(height) = self.height {
+                settings.window_settings.height = height;
+            }
+            if self.fullscreen {
+                settings.display_settings.window_mode = WindowMode::Fullscreen;
+            }
+            if let Some(difficulty) = self.difficulty {
+                settings.difficulty = difficulty;
+            }
+            *settings = std::mem::take(settings).validated();
+        });
+        if self.seed.is_some() {
+            settings.seed_override = self.seed;
+        }
+    }
+}
+
+/// Simulates `matches` matches with `settings` as fast as possible and prints how each one ended.
+///
+/// The AI plays both paddles, at the difficulty of the settings. Survival matches end the first time
+/// the left paddle concedes, and every match is stopped after `HEADLESS_MAX_SECONDS` if it didn't end.
+/// Matches with a fixed seed are seeded with consecutive numbers from it, so they are not all the same.
+pub fn run_headless(settings: &Settings, matches: u32) {
+    let seed = settings.match_seed();
+    let fixed_step = settings.physics_settings.fixed_step;
+    let power_ups = power_up::read_rules();
+    let level = Levels::read().get(settings.level.as_deref()).clone();
+    let ai = Controller::Ai(settings.difficulty.profile());
+    for index in 0..matches {
+        let config = SimulationConfig {
+            match_rules: settings.match_rules,
+            controllers: [ai.clone(), ai.clone()],
+            seed: seed.wrapping_add(index as u64),
+            ball_mode: settings.ball_mode,
+            power_ups: power_ups.clone(),
+            bounce: settings.bounce,
+            level: level.clone(),
+        };
+        let mut simulation = Simulation::new(config);
+        let mut conceded = false;
+        while !conceded && simulation.match_result().is_none() && simulation.elapsed() < HEADLESS_MAX_SECONDS {
+            let events = simulation.step(fixed_step, &TickInput::default());
+            conceded = settings.match_rules == MatchRules::Survival
+                && events.contains(&SimEvent::Goal { side: Side::Left });
+        }
+        let score = simulation.score();
+        let result = match simulation.match_result() {
+            Some(MatchResult::Winner(side)) => format!("{:?} wins {}-{}", side, score.left, score.right),
+            Some(MatchResult::Draw) => format!("Draw {}-{}", score.left, score.right),
+            None if conceded => "Left conceded".to_string(),
+            None => format!("Stopped at {}-{}", score.left, score.right),
+        };
+        println!("match {} seed {}: {} after {:.1}s", index + 1, simulation.seed(), result, simulation.elapsed());
+    }
+}
+
+/// The value following `option`.
+pub fn value(args: &mut impl Iterator<Item = String>, option: &'static str) -> Result<String, CliError> {
+    args.next().ok_or(CliError::MissingValue(option))
+}
+
+/// The value following `option`, parsed and checked with `valid`.
+pub fn parse_value<T: std::str::FromStr>(args: &mut impl Iterator<Item = String>, option: &'static str, valid: impl Fn(&T) -> bool) -> Result<T, CliError> {
+    let value = value(args, option)?;
+    match value.parse::<T>() {
+        Ok(parsed) if valid(&parsed) => Ok(parsed),
+        _ => Err(CliError::InvalidValue { option, value }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, CliError> {
+        Cli::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_every_option() {
+        let cli = parse(&[
+            "--config", "other.ron", "--width", "800", "--height", "600", "--fullscreen", "--difficulty", "Hard",
+            "--seed", "1234", "--skip-intro", "--replay", "last.ron", "--headless-sim", "3", "--help",
+        ]).unwrap();
+        assert_eq!(cli, Cli {
+            config: Some(PathBuf::from("other.ron")),
+            width: Some(800.0),
+            height: Some(600.0),
+            fullscreen: true,
+            difficulty: Some(Difficulty::Hard),
+            seed: Some(1234),
+            skip_intro: true,
+            replay: Some(PathBuf::from("last.ron")),
+            headless_sim: Some(3),
+            help: true,
+        });
+        assert_eq!(parse(&[]).unwrap(), Cli::default());
+    }
+
+    #[test]
+    fn rejects_unknown_missing_and_invalid_values() {
+        assert_eq!(parse(&["--nope"]), Err(CliError::Unknown("--nope".to_string())));
+        assert_eq!(parse(&["--seed"]), Err(CliError::MissingValue("--seed")));
+        assert_eq!(parse(&["--width", "-5"]), Err(CliError::InvalidValue { option: "--width", value: "-5".to_string() }));
+        assert_eq!(parse(&["--headless-sim", "many"]), Err(CliError::InvalidValue { option: "--headless-sim", value: "many".to_string() }));
+    }
+
+    #[test]
+    fn options_are_not_saved() {
+        let mut settings = Settings::default();
+        parse(&["--width", "800", "--fullscreen", "--difficulty", "Easy", "--seed", "7"]).unwrap().apply(&mut settings);
+        assert_eq!(settings.window_settings.width, 800.0);
+        assert_eq!(settings.display_settings.window_mode, WindowMode::Fullscreen);
+        assert_eq!(settings.difficulty, Difficulty::Easy);
+        assert_eq!(settings.match_seed(), 7);
+
+        let saved = ron::from_str::<Settings>(&ron::to_string(&settings.to_save()).unwrap()).unwrap();
+        let defaults = Settings::default();
+        assert_eq!(saved.window_settings, defaults.window_settings);
+        assert_eq!(saved.display_settings.window_mode, defaults.display_settings.window_mode);
+        assert_eq!(saved.difficulty, defaults.difficulty);
+        assert_eq!(saved.seed, None);
+    }
+
+    #[test]
+    fn options_changed_again_in_the_game_are_saved() {
+        let mut settings = Settings::default();
+        parse(&["--difficulty", "Easy"]).unwrap().apply(&mut settings);
+        settings.difficulty = Difficulty::Hard;
+        assert_eq!(settings.to_save().difficulty, Difficulty::Hard);
+    }
+}
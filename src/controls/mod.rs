@@ -0,0 +1,202 @@
+use amethyst::input::{Axis, Bindings, Button, StringBindings};
+
+use crate::menu::{CONFIRM_ACTION, PAUSE_ACTION};
+use crate::persistence::paths;
+
+pub const BINDINGS_FILE: &str = "bindings.ron";
+
+/// Something the player can bind a key or gamepad button to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Control {
+    /// Pushes `axis` to its positive or its negative end.
+    Axis { axis: &'static str, positive: bool },
+    Action(&'static str),
+}
+
+/// A control as listed on the controls screen.
+#[derive(Debug, Copy, Clone)]
+pub struct Rebindable {
+    /// Id of the button showing the binding in `ui/controls.ron`.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub control: Control,
+}
+
+pub const REBINDABLE: [Rebindable; 6] = [
+    Rebindable { id: "left_up", name: "P1 UP", control: Control::Axis { axis: "left_paddle", positive: true } },
+    Rebindable { id: "left_down", name: "P1 DOWN", control: Control::Axis { axis: "left_paddle", positive: false } },
+    Rebindable { id: "right_up", name: "P2 UP", control: Control::Axis { axis: "right_paddle", positive: true } },
+    Rebindable { id: "right_down", name: "P2 DOWN", control: Control::Axis { axis: "right_paddle", positive: false } },
+    Rebindable { id: "pause", name: "PAUSE", control: Control::Action(PAUSE_ACTION) },
+    Rebindable { id: "confirm", name: "CONFIRM", control: Control::Action(CONFIRM_ACTION) },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RebindError {
+    /// The button already does something else, named after the control using it if it is listed.
+    Conflict(String),
+    /// The control has no binding for the device the button belongs to, such as a third gamepad.
+    UnsupportedDevice,
+}
+
+/// The keys and buttons that trigger `control`.
+pub fn bound_buttons(bindings: &Bindings<StringBindings>, control: Control) -> Vec<Button> {
+    match control {
+        Control::Axis { axis, positive } => {
+            let mut buttons = vec![];
+            if let Some(axis) = bindings.axis(axis) {
+                collect_axis_buttons(axis, positive, &mut buttons);
+            }
+            buttons
+        }
+        Control::Action(action) => bindings.action_bindings(action)
+            .filter(|combo| combo.len() == 1)
+            .map(|combo| combo[0])
+            .collect(),
+    }
+}
+
+/// Binds `button` to `control`, replacing whatever the control was bound to on the same device.
+///
+/// The bindings are left untouched when the button is already in use.
+pub fn rebind(bindings: &mut Bindings<StringBindings>, control: Control, button: Button) -> Result<(), RebindError> {
+    if let Some(other) = REBINDABLE.iter()
+        .find(|other| other.control != control && bound_buttons(bindings, other.control).contains(&button))
+    {
+        return Err(RebindError::Conflict(other.name.to_string()));
+    }
+    match control {
+        Control::Axis { axis: id, positive } => {
+            let old = bindings.axis(id).cloned().ok_or(RebindError::UnsupportedDevice)?;
+            let mut new = old.clone();
+            if !replace_axis_button(&mut new, positive, button) {
+                return Err(RebindError::UnsupportedDevice);
+            }
+            bindings.remove_axis(id);
+            if let Err(e) = bindings.insert_axis(id, new) {
+                bindings.insert_axis(id, old).expect("unreachable: The old axis was bound before");
+                return Err(RebindError::Conflict(e.to_string()));
+            }
+        }
+        Control::Action(id) => {
+            let old = bound_buttons(bindings, control).into_iter()
+                .find(|old| same_device(*old, button));
+            if let Some(old) = old {
+                bindings.remove_action_binding(id, &[old]).expect("unreachable: The old button was bound before");
+            }
+            if let Err(e) = bindings.insert_action_binding(id.to_string(), vec![button]) {
+                if let Some(old) = old {
+                    bindings.insert_action_binding(id.to_string(), vec![old]).expect("unreachable: The old button was bound before");
+                }
+                return Err(RebindError::Conflict(e.to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A short name for a key or button, gamepad buttons are prefixed with the player they belong to.
+pub fn button_name(button: Button) -> String {
+    match button {
+        Button::Key(key) => format!("{:?}", key),
+        Button::Controller(id, button) => format!("P{} {:?}", id + 1, button),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Reads the saved bindings, or the bundled ones if they were never saved or can't be read.
+pub fn read_or_default() -> Bindings<StringBindings> {
+    paths::read_ron(&paths::user_config_dir().join(BINDINGS_FILE), "bindings", parse_bindings)
+        .or_else(|| paths::read_ron(&paths::bundled_config_dir().join(BINDINGS_FILE), "bindings", parse_bindings))
+        .unwrap_or_default()
+}
+
+fn parse_bindings(content: &str) -> Result<Bindings<StringBindings>, String> {
+    let mut bindings: Bindings<StringBindings> = ron::from_str(content).map_err(|e| e.to_string())?;
+    bindings.check_invariants().map_err(|e| e.to_string())?;
+    Ok(bindings)
+}
+
+pub fn persist_async(bindings: &Bindings<StringBindings>) {
+    paths::persist_ron_async(paths::user_config_dir().join(BINDINGS_FILE), "bindings", bindings.clone());
+}
+
+fn collect_axis_buttons(axis: &Axis, positive: bool, buttons: &mut Vec<Button>) {
+    match axis {
+        Axis::Emulated { pos, neg } => buttons.push(if positive { *pos } else { *neg }),
+        Axis::Multiple(axes) => axes.iter().for_each(|axis| collect_axis_buttons(axis, positive, buttons)),
+        _ => {}
+    }
+}
+
+fn replace_axis_button(axis: &mut Axis, positive: bool, button: Button) -> bool {
+    match axis {
+        Axis::Emulated { pos, neg } => {
+            let old = if positive { pos } else { neg };
+            if same_device(*old, button) {
+                *old = button;
+                true
+            } else {
+                false
+            }
+        }
+        Axis::Multiple(axes) => axes.iter_mut().any(|axis| replace_axis_button(axis, positive, button)),
+        _ => false,
+    }
+}
+
+fn same_device(a: Button, b: Button) -> bool {
+    match (a, b) {
+        (Button::Key(_), Button::Key(_)) | (Button::ScanCode(_), Button::ScanCode(_)) => true,
+        (Button::Key(_), Button::ScanCode(_)) | (Button::ScanCode(_), Button::Key(_)) => true,
+        (Button::Mouse(_), Button::Mouse(_)) => true,
+        (Button::Controller(a, _), Button::Controller(b, _)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst::input::{ControllerButton, VirtualKeyCode};
+
+    use super::*;
+
+    const LEFT_UP: Control = Control::Axis { axis: "left_paddle", positive: true };
+    const CONFIRM: Control = Control::Action(CONFIRM_ACTION);
+
+    fn bundled() -> Bindings<StringBindings> {
+        parse_bindings(include_str!("../../config/bindings.ron")).unwrap()
+    }
+
+    #[test]
+    fn a_button_used_by_another_control_is_a_conflict() {
+        let mut bindings = bundled();
+        assert_eq!(rebind(&mut bindings, LEFT_UP, Button::Key(VirtualKeyCode::Down)), Err(RebindError::Conflict("P2 DOWN".to_string())));
+        assert_eq!(rebind(&mut bindings, CONFIRM, Button::Controller(0, ControllerButton::Start)), Err(RebindError::Conflict("PAUSE".to_string())));
+        assert_eq!(bound_buttons(&bindings, LEFT_UP), bound_buttons(&bundled(), LEFT_UP));
+        assert_eq!(bound_buttons(&bindings, CONFIRM), bound_buttons(&bundled(), CONFIRM));
+    }
+
+    #[test]
+    fn rebinding_replaces_the_button_of_the_same_device() {
+        let mut bindings = bundled();
+        rebind(&mut bindings, LEFT_UP, Button::Key(VirtualKeyCode::E)).unwrap();
+        let buttons = bound_buttons(&bindings, LEFT_UP);
+        assert!(buttons.contains(&Button::Key(VirtualKeyCode::E)));
+        assert!(!buttons.contains(&Button::Key(VirtualKeyCode::W)));
+        assert!(buttons.contains(&Button::Controller(0, ControllerButton::DPadUp)));
+    }
+
+    #[test]
+    fn rebinding_to_its_own_button_is_no_conflict() {
+        let mut bindings = bundled();
+        assert_eq!(rebind(&mut bindings, LEFT_UP, Button::Key(VirtualKeyCode::W)), Ok(()));
+        assert_eq!(rebind(&mut bindings, CONFIRM, Button::Key(VirtualKeyCode::Return)), Ok(()));
+    }
+
+    #[test]
+    fn a_device_the_control_has_no_binding_for_is_unsupported() {
+        let mut bindings = bundled();
+        assert_eq!(rebind(&mut bindings, LEFT_UP, Button::Controller(2, ControllerButton::A)), Err(RebindError::UnsupportedDevice));
+    }
+}
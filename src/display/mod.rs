@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use amethyst::core::ecs::World;
+use amethyst::core::frame_limiter::{FrameLimiter, FrameRateLimitStrategy};
+use amethyst::window::Window;
+use amethyst::winit::MonitorId;
+
+use crate::persistence::{DisplaySettings, WindowMode};
+
+/// The frame rate caps cycled through on the display screen, 0 is no limit.
+pub const FPS_CAPS: [u32; 6] = [30, 60, 120, 144, 240, 0];
+
+/// Applies the window mode, monitor and frame rate cap to the running game.
+pub fn apply_display_settings(world: &mut World, settings: &DisplaySettings) {
+    if let Some(mut frame_limiter) = world.try_fetch_mut::<FrameLimiter>() {
+        // Sleeping most of the frame and yielding the rest keeps the cap accurate without spinning.
+        frame_limiter.set_rate(FrameRateLimitStrategy::SleepAndYield(Duration::from_millis(2)), settings.fps_cap);
+    }
+    let window = match world.try_fetch::<Window>() {
+        Some(window) => window,
+        None => return,
+    };
+    let monitor = monitor(&window, settings.monitor);
+    match settings.window_mode {
+        WindowMode::Windowed => {
+            window.set_fullscreen(None);
+            window.set_decorations(true);
+        }
+        WindowMode::Borderless => {
+            window.set_fullscreen(None);
+            window.set_decorations(false);
+            let hidpi_factor = monitor.get_hidpi_factor();
+            window.set_position(monitor.get_position().to_logical(hidpi_factor));
+            window.set_inner_size(monitor.get_dimensions().to_logical(hidpi_factor));
+        }
+        WindowMode::Fullscreen => window.set_fullscreen(Some(monitor)),
+    }
+}
+
+/// Number of monitors the window can be shown on.
+pub fn monitor_count(world: &World) -> usize {
+    // Some platforms list no monitors until they announce their outputs, there is still the one showing the window.
+    world.try_fetch::<Window>()
+        .map(|window| window.get_available_monitors().count().max(1))
+        .unwrap_or(1)
+}
+
+/// The monitor at `index`, or the primary one when there is no such monitor anymore.
+fn monitor(window: &Window, index: usize) -> MonitorId {
+    window.get_available_monitors()
+        .nth(index)
+        .unwrap_or_else(|| window.get_primary_monitor())
+}
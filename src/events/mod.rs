@@ -0,0 +1,37 @@
+use amethyst::shrev::EventChannel;
+
+use crate::simulation::SimEvent;
+use crate::simulation::paddle::Side;
+use crate::simulation::power_up::PowerUpKind;
+
+/// What happens in a match, published by the physics systems on an `EventChannel<GameEvent>`
+/// for sounds, the timer, the taunt, statistics and the replay to react to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameEvent {
+    /// `speed` is the horizontal speed the ball left the paddle with, in arena units per second.
+    PaddleHit { side: Side, speed: f32 },
+    /// The ball bounced off the top or bottom of the arena.
+    WallHit,
+    /// The ball entered the goal behind the paddle on `side`.
+    Goal { side: Side },
+    /// The first ball of the match was served.
+    Serve,
+    /// A ball is in play again, after the serve and after every goal.
+    RallyStart,
+    /// A ball collected a power-up for the paddle on `side`.
+    PowerUp { kind: PowerUpKind, side: Side },
+}
+
+/// Publishes the game events of what happened in the simulation.
+pub fn publish(channel: &mut EventChannel<GameEvent>, events: Vec<SimEvent>) {
+    for event in events {
+        match event {
+            SimEvent::Serve => channel.iter_write(vec![GameEvent::Serve, GameEvent::RallyStart]),
+            SimEvent::PaddleHit { side, speed } => channel.single_write(GameEvent::PaddleHit { side, speed }),
+            SimEvent::WallBounce => channel.single_write(GameEvent::WallHit),
+            // The ball is put back in the middle right away.
+            SimEvent::Goal { side } => channel.iter_write(vec![GameEvent::Goal { side }, GameEvent::RallyStart]),
+            SimEvent::PowerUp { kind, side } => channel.single_write(GameEvent::PowerUp { kind, side }),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use amethyst::core::RunNowDesc;
+use amethyst::ecs::{System, SystemData, World};
+use amethyst::input::{SdlEventsSystem, StringBindings};
+
+/// Feeds gamepads into the `InputHandler`, or does nothing when SDL failed to start, since
+/// gamepads are optional and the keyboard still works.
+pub struct GamepadSystem {
+    sdl_events: Option<SdlEventsSystem<StringBindings>>,
+}
+
+impl<'a> System<'a> for GamepadSystem {
+    type SystemData = <SdlEventsSystem<StringBindings> as System<'a>>::SystemData;
+
+    fn run(&mut self, data: Self::SystemData) {
+        if let Some(sdl_events) = self.sdl_events.as_mut() {
+            sdl_events.run(data);
+        }
+    }
+}
+
+/// Builds the system that feeds gamepads into the `InputHandler`, including ones plugged in while
+/// the game runs.
+///
+/// Amethyst keeps its own builder for this system private, and SDL has to stay on the main
+/// thread, so it is added as a thread local system.
+#[derive(Default)]
+pub struct GamepadSystemDesc;
+
+impl<'a, 'b> RunNowDesc<'a, 'b, GamepadSystem> for GamepadSystemDesc {
+    fn build(self, world: &mut World) -> GamepadSystem {
+        <SdlEventsSystem<StringBindings> as System<'_>>::SystemData::setup(world);
+        let sdl_events = SdlEventsSystem::new(world, None)
+            .map_err(|e| log::error!("Failed to start gamepad support, playing without gamepads: {}", e))
+            .ok();
+        GamepadSystem { sdl_events }
+    }
+}
@@ -0,0 +1,119 @@
+pub mod record_system;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use amethyst::core::ecs::Entity;
+
+use crate::persistence::{paths, Settings};
+use crate::simulation::ai::Difficulty;
+use crate::simulation::arena::ARENA;
+use crate::simulation::controller::Players;
+use crate::simulation::level::Level;
+use crate::simulation::power_up::PowerUpRules;
+use crate::simulation::rules::MatchRules;
+
+const HIGH_SCORES_FILE: &str = "high_scores.ron";
+
+/// Number of survival times kept in the table.
+pub const MAX_HIGH_SCORES: usize = 10;
+
+/// The longest survival times against the AI, best first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub seconds: f32,
+    /// Day the time was set on, as `YYYY-MM-DD` in UTC.
+    pub date: String,
+    pub difficulty: Difficulty,
+    /// See `fingerprint`.
+    pub fingerprint: String,
+}
+
+impl HighScores {
+    /// Reads the saved high scores, or an empty table if there are none yet.
+    pub fn read_or_default() -> HighScores {
+        paths::read_ron_or_default(&paths::user_config_dir().join(HIGH_SCORES_FILE), "high scores")
+    }
+
+    pub fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// The longest time survived on `difficulty` with settings of the same `fingerprint`.
+    pub fn best(&self, difficulty: Difficulty, fingerprint: &str) -> Option<f32> {
+        self.entries.iter()
+            .find(|entry| entry.difficulty == difficulty && entry.fingerprint == fingerprint)
+            .map(|entry| entry.seconds)
+    }
+
+    /// Adds `score` to the table, returns its rank from 0 or `None` if it was too short to make it.
+    pub fn submit(&mut self, score: HighScore) -> Option<usize> {
+        let rank = self.entries.iter().position(|entry| score.seconds > entry.seconds).unwrap_or(self.entries.len());
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.entries.insert(rank, score);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+
+    pub fn persist_async(&self) {
+        paths::persist_ron_async(paths::user_config_dir().join(HIGH_SCORES_FILE), "high scores", self.clone());
+    }
+}
+
+/// The banner shown once the player survives longer than their best time.
+#[derive(Default)]
+pub struct RecordText {
+    pub banner: Option<Entity>,
+    /// The best time with the current difficulty and settings, `None` when there is none yet.
+    pub to_beat: Option<f32>,
+}
+
+/// Whether matches played with `settings` are survival runs against the AI that go in the table.
+pub fn tracks(settings: &Settings) -> bool {
+    settings.match_rules == MatchRules::Survival && settings.players == Players::OnePlayer
+}
+
+/// Identifies everything besides the difficulty that changes how hard surviving is, so times
+/// are only compared with times played the same way.
+pub fn fingerprint(settings: &Settings, power_ups: &PowerUpRules, level: &Level) -> String {
+    let fixed_seed = settings.seed_override.or(settings.seed).is_some();
+    let mut played_with = format!("{:?}", (
+        settings.physics_settings.fixed_step,
+        fixed_seed,
+        ARENA,
+        settings.ball_mode,
+        settings.bounce,
+    ));
+    // Disabled power-ups play the same whatever else their rules say.
+    if power_ups.enabled {
+        played_with.push_str(&format!("{:?}", power_ups));
+    }
+    if *level != Level::default() {
+        played_with.push_str(&format!("{:?}", level));
+    }
+    // FNV-1a, unlike the std hashers it gives the same result on every build.
+    let hash = played_with.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3));
+    format!("{:016x}", hash)
+}
+
+/// Today's date as `YYYY-MM-DD` in UTC.
+pub fn today() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or(0);
+    // Days since the epoch to a civil date, from http://howardhinnant.github.io/date_algorithms.html
+    let z = seconds / 86_400 + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
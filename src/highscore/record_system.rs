@@ -0,0 +1,33 @@
+use amethyst::core::ecs::{Read, System, WriteStorage};
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::SystemData;
+use amethyst::ui::UiText;
+
+use crate::highscore::RecordText;
+use crate::simulation::Simulation;
+
+const NEW_RECORD: &str = "NEW RECORD";
+
+/// Shows the new record banner while the current run is longer than the best one.
+#[derive(SystemDesc)]
+pub struct RecordSystem;
+
+impl<'s> System<'s> for RecordSystem {
+    type SystemData = (
+        Read<'s, RecordText>,
+        Read<'s, Simulation>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (record_text, simulation, mut ui_text): Self::SystemData) {
+        if let Some(banner) = record_text.banner {
+            if let Some(ui) = ui_text.get_mut(banner) {
+                let beaten = record_text.to_beat.is_some_and(|best| simulation.survived() > best);
+                let text = if beaten { NEW_RECORD } else { "" };
+                if ui.text != text {
+                    ui.text = text.to_string();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use amethyst::core::ecs::{Component, DenseVecStorage};
+
+/// Links an entity to the obstacle at `index` of the `Simulation`'s obstacles.
+pub struct Obstacle {
+    pub index: usize,
+}
+
+impl Component for Obstacle {
+    type Storage = DenseVecStorage<Self>;
+}
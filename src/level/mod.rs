@@ -0,0 +1,89 @@
+pub mod component;
+
+use std::path::PathBuf;
+
+use amethyst::utils::application_root_dir;
+
+use crate::simulation::level::Level;
+use crate::simulation::obstacle::Obstacle;
+
+const LEVELS_DIR: &str = "assets/levels";
+const BLOCK_SPRITE: usize = 11;
+const DISC_SPRITE: usize = 12;
+
+/// A level the player can pick, `id` is the name of its file without the extension.
+#[derive(Debug, Clone)]
+pub struct LevelEntry {
+    /// `None` for the classic empty arena, which has no file.
+    pub id: Option<String>,
+    pub level: Level,
+}
+
+/// The classic arena followed by the levels in `assets/levels`, sorted by file name.
+#[derive(Debug, Clone)]
+pub struct Levels {
+    entries: Vec<LevelEntry>,
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        Levels { entries: vec![LevelEntry { id: None, level: Level::default() }] }
+    }
+}
+
+impl Levels {
+    /// Reads every level, the ones that fail to parse are left out.
+    pub fn read() -> Levels {
+        let mut levels = Levels::default();
+        let dir = levels_dir();
+        let mut paths: Vec<PathBuf> = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+                .collect(),
+            Err(e) => {
+                log::warn!("Failed to list levels in {}: {}", dir.display(), e);
+                vec![]
+            }
+        };
+        paths.sort();
+        for path in paths {
+            let id = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| ron::from_str::<Level>(&content).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(level) => levels.entries.push(LevelEntry { id, level: level.validated() }),
+                Err(e) => log::warn!("Failed to read level {}, leaving it out: {}", path.display(), e),
+            }
+        }
+        levels
+    }
+
+    pub fn entries(&self) -> &[LevelEntry] {
+        &self.entries
+    }
+
+    /// Position of the level `id` in the list, the classic arena when it is unknown.
+    pub fn position(&self, id: Option<&str>) -> usize {
+        self.entries.iter().position(|entry| entry.id.as_deref() == id).unwrap_or(0)
+    }
+
+    /// The level `id`, the classic arena when it is unknown.
+    pub fn get(&self, id: Option<&str>) -> &Level {
+        &self.entries[self.position(id)].level
+    }
+}
+
+fn levels_dir() -> PathBuf {
+    application_root_dir()
+        .map(|root| root.join(LEVELS_DIR))
+        .unwrap_or_else(|_| PathBuf::from(LEVELS_DIR))
+}
+
+pub fn sprite_number(obstacle: &Obstacle) -> usize {
+    match obstacle {
+        Obstacle::Rect { .. } => BLOCK_SPRITE,
+        Obstacle::Circle { .. } => DISC_SPRITE,
+    }
+}
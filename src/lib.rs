@@ -0,0 +1,28 @@
+#![allow(clippy::module_inception)]
+
+pub mod state;
+pub mod timer;
+pub mod audio;
+pub mod winner;
+pub mod ball;
+pub mod paddle;
+pub mod taunt;
+pub mod persistence;
+pub mod score;
+pub mod simulation;
+pub mod sync;
+pub mod menu;
+pub mod controls;
+pub mod display;
+pub mod replay;
+pub mod highscore;
+pub mod stats;
+pub mod events;
+pub mod power_up;
+pub mod level;
+pub mod cli;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+
+#[macro_use]
+extern crate serde;
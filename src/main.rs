@@ -1,11 +1,3 @@
-mod state;
-mod timer;
-mod audio;
-mod winner;
-mod ball;
-mod paddle;
-mod taunt;
-mod persistence;
 
 use amethyst::{
     prelude::*,
@@ -19,27 +11,65 @@ use amethyst::{
 };
 use amethyst::core::TransformBundle;
 use amethyst::input::{InputBundle, StringBindings};
+#[cfg(feature = "gamepad")]
+use impossi_pong::gamepad::GamepadSystemDesc;
 use amethyst::ui::{RenderUi, UiBundle};
 use amethyst::audio::{AudioBundle, DjSystemDesc};
-use crate::audio::audio::Music;
-use state::start::StartScreen;
+use impossi_pong::{highscore, menu, power_up, score, sync, taunt, timer};
+use impossi_pong::audio::audio::Music;
+use impossi_pong::audio::sound_system;
+use impossi_pong::state::start::{Launch, StartScreen};
+use impossi_pong::cli::{self, Cli, USAGE};
+use impossi_pong::replay::Replay;
 use amethyst::window::{DisplayConfig};
 use amethyst::winit::Icon;
-use crate::persistence::Settings;
-
-#[macro_use]
-extern crate serde;
+use impossi_pong::persistence::Settings;
+use impossi_pong::controls;
+use std::time::Duration;
 
 fn main() -> Result<()> {
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if cli.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
     amethyst::start_logger(Default::default());
 
+    if let Some(path) = &cli.config {
+        Settings::use_path(path);
+    }
+    let mut settings = Settings::read_or_default();
+    cli.apply(&mut settings);
+    if let Some(matches) = cli.headless_sim {
+        cli::run_headless(&settings, matches);
+        return Ok(());
+    }
+    let launch = match &cli.replay {
+        Some(path) => match Replay::load(path) {
+            Ok(replay) => Launch::Replay(Box::new(replay)),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None if cli.skip_intro => Launch::MainMenu,
+        None => Launch::Intro,
+    };
+
     let app_root = application_root_dir()?;
-    let mut display_config = DisplayConfig::default();
-    let settings = Settings::read_or_default();
-    display_config.loaded_icon = Some(Icon::from_path("assets/texture/logo.png")?);
-    display_config.dimensions = Some((settings.window_settings.arena_width() as u32, settings.window_settings.arena_height() as u32));
-    let input_bundle = InputBundle::<StringBindings>::new()
-        .with_bindings_from_file(app_root.join("config").join("bindings.ron"))?;
+    // The window mode and frame rate cap are applied once the window exists, see `apply_display_settings`.
+    let display_config = DisplayConfig {
+        loaded_icon: Some(Icon::from_path(app_root.join("assets").join("texture").join("logo.png"))?),
+        dimensions: Some((settings.window_settings.width() as u32, settings.window_settings.height() as u32)),
+        ..Default::default()
+    };
+    let input_bundle = InputBundle::<StringBindings>::new().with_bindings(controls::read_or_default());
     let game_data = GameDataBuilder::default()
         .with_bundle(
             RenderingBundle::<DefaultBackend>::new()
@@ -61,16 +91,22 @@ fn main() -> Result<()> {
             "dj_system",
             &[],
         )
-        .with(ball::ball_system::MoveBallsSystem, "ball_system", &[])
-        .with(timer::timer_system::TimerSystem, "timer_system", &[])
-        .with(taunt::taunt_system::TauntSystem, "taunt_system", &[])
-        .with(ball::trajectory_system::TrajectorySystem, "trajectory_system", &["ball_system"])
-        .with(paddle::paddle::PaddleSystem, "paddle_system", &["input_system", "trajectory_system"])
-        .with(ball::bounce_system::BounceSystem, "collision_system", &["paddle_system", "ball_system"])
-        .with(winner::winner::WinnerSystem, "winner_system", &["ball_system"]);
+        .with_system_desc(timer::timer_system::TimerSystemDesc, "timer_system", &[])
+        .with(score::score_system::ScoreSystem, "score_system", &[])
+        .with(highscore::record_system::RecordSystem, "record_system", &["timer_system"])
+        .with_system_desc(taunt::taunt_system::TauntSystemDesc, "taunt_system", &[])
+        .with_system_desc(sound_system::SoundSystemDesc, "sound_system", &[])
+        .with(sync::sync_system::TransformSyncSystem, "transform_sync_system", &[])
+        .with(power_up::fog_system::FogSystem, "fog_system", &[])
+        .with(menu::menu_system::MenuSystem::default(), "menu_system", &[]);
+    #[cfg(feature = "gamepad")]
+    let game_data = game_data.with_thread_local_desc(GamepadSystemDesc);
 
     let assets_dir = app_root.join("assets");
-    let mut game = Application::new(assets_dir, StartScreen::new(settings), game_data)?;
+    let fixed_step = Duration::from_secs_f32(settings.physics_settings.fixed_step);
+    let mut game = Application::build(assets_dir, StartScreen::launching(settings, launch))?
+        .with_fixed_step_length(fixed_step)
+        .build(game_data)?;
     game.run();
     Ok(())
 }
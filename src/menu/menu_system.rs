@@ -0,0 +1,69 @@
+use amethyst::core::ecs::{Entities, Join, Read, System, Write, WriteStorage};
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::SystemData;
+use amethyst::input::{InputHandler, StringBindings};
+use amethyst::shrev::EventChannel;
+use amethyst::ui::{CachedSelectionOrder, Selected, UiEvent, UiEventType};
+
+use crate::menu::{CONFIRM_ACTION, NAVIGATION_AXES};
+
+/// How far an axis has to be pushed before it moves the selection.
+const NAVIGATION_THRESHOLD: f32 = 0.5;
+
+/// Lets menus be used without a mouse.
+///
+/// Pushing a paddle axis up or down selects the previous or next selectable button, and the
+/// confirm action clicks the selected one, so states only ever have to handle clicks.
+#[derive(SystemDesc, Default)]
+pub struct MenuSystem {
+    /// Direction the navigation axes were pushed in last frame, the selection only moves once per push.
+    held_direction: i32,
+    confirm_held: bool,
+}
+
+impl<'s> System<'s> for MenuSystem {
+    type SystemData = (
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, CachedSelectionOrder>,
+        WriteStorage<'s, Selected>,
+        Write<'s, EventChannel<UiEvent>>,
+        Entities<'s>,
+    );
+
+    fn run(&mut self, (input, cached, mut selecteds, mut ui_events, entities): Self::SystemData) {
+        let value = NAVIGATION_AXES.iter()
+            .filter_map(|axis| input.axis_value(*axis))
+            .fold(0.0f32, |strongest, value| if value.abs() > strongest.abs() { value } else { strongest });
+        let direction = if value >= NAVIGATION_THRESHOLD {
+            -1
+        } else if value <= -NAVIGATION_THRESHOLD {
+            1
+        } else {
+            0
+        };
+        if direction != 0 && direction != self.held_direction && !cached.cache.is_empty() {
+            let target = match cached.highest_order_selected_index(&selecteds) {
+                Some(current) => (current as i32 + direction).rem_euclid(cached.cache.len() as i32) as usize,
+                None => 0,
+            };
+            for (entity, _) in (&*entities, &selecteds).join() {
+                ui_events.single_write(UiEvent::new(UiEventType::HoverStop, entity));
+                ui_events.single_write(UiEvent::new(UiEventType::Blur, entity));
+            }
+            selecteds.clear();
+            let target = cached.cache[target].1;
+            selecteds.insert(target, Selected).expect("unreachable: We are inserting");
+            ui_events.single_write(UiEvent::new(UiEventType::Focus, target));
+            ui_events.single_write(UiEvent::new(UiEventType::HoverStart, target));
+        }
+        self.held_direction = direction;
+
+        let confirm = input.action_is_down(CONFIRM_ACTION).unwrap_or(false);
+        if confirm && !self.confirm_held {
+            if let Some(selected) = cached.highest_order_selected_index(&selecteds) {
+                ui_events.single_write(UiEvent::new(UiEventType::Click, cached.cache[selected].1));
+            }
+        }
+        self.confirm_held = confirm;
+    }
+}
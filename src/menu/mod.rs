@@ -0,0 +1,8 @@
+pub mod menu_system;
+
+/// Clicks the selected menu button, bound to Return and the A button of every gamepad.
+pub const CONFIRM_ACTION: &str = "confirm";
+/// Opens and closes the pause menu, bound to the Start button of every gamepad.
+pub const PAUSE_ACTION: &str = "pause";
+/// The axes that move the selection through a menu, the same ones that move the paddles.
+pub const NAVIGATION_AXES: [&str; 2] = ["left_paddle", "right_paddle"];
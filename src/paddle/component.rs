@@ -1,23 +1,16 @@
 use amethyst::core::ecs::{Component, DenseVecStorage};
 
-#[derive(PartialEq, Eq)]
-pub enum Side {
-    Left,
-    Right,
-}
+pub use crate::simulation::paddle::Side;
 
+/// Links an entity to its paddle in the `Simulation`.
 pub struct Paddle {
     pub side: Side,
-    pub width: f32,
-    pub height: f32,
 }
 
 impl Paddle {
-    pub fn new(side: Side, height: f32, width: f32) -> Paddle {
+    pub fn new(side: Side) -> Paddle {
         Paddle {
             side,
-            width,
-            height,
         }
     }
 }
@@ -0,0 +1,88 @@
+use super::Settings;
+use crate::simulation::ball::Bounce;
+
+/// Bumped whenever the layout of the settings changes in a way that needs a migration.
+pub const SETTINGS_VERSION: u32 = 3;
+
+/// Settings saved before they had a version.
+const UNVERSIONED: u32 = 1;
+
+/// Only the version of saved settings, read first to know which migrations they need.
+#[derive(Deserialize)]
+struct SettingsVersion {
+    #[serde(default = "unversioned")]
+    version: u32,
+}
+
+fn unversioned() -> u32 {
+    UNVERSIONED
+}
+
+/// Turns settings saved by version `n + 1` into the layout of version `n + 2`.
+const MIGRATIONS: [fn(&mut Settings); (SETTINGS_VERSION - UNVERSIONED) as usize] = [
+    // Unversioned settings only lack fields that have a default.
+    |_| {},
+    // Balls bounce off paddles at an angle by default now, settings saved before keep the classic bounce they played with.
+    |settings| settings.bounce = Bounce::Classic,
+];
+
+/// Parses saved settings of any version, migrating them to the current one.
+pub fn parse(content: &str) -> Result<Settings, ron::Error> {
+    let version = ron::from_str::<SettingsVersion>(content)?.version;
+    let mut settings = ron::from_str::<Settings>(content)?;
+    if version > SETTINGS_VERSION {
+        log::warn!("Settings were saved by a newer version {}, reading what version {} knows of them", version, SETTINGS_VERSION);
+    }
+    for (from, migration) in (UNVERSIONED..).zip(MIGRATIONS.iter()) {
+        if version <= from {
+            log::info!("Migrating settings from version {} to {}", from, from + 1);
+            migration(&mut settings);
+        }
+    }
+    settings.version = SETTINGS_VERSION;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::ai::Difficulty;
+
+    #[test]
+    fn unversioned_settings_are_migrated_to_the_current_version() {
+        let settings = parse("(difficulty: Hard, seed: Some(12))").unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.difficulty, Difficulty::Hard);
+        assert_eq!(settings.seed, Some(12));
+    }
+
+    #[test]
+    fn every_older_version_is_migrated_to_the_current_version() {
+        for version in UNVERSIONED..=SETTINGS_VERSION {
+            let settings = parse(&format!("(version: {}, difficulty: Easy)", version)).unwrap();
+            assert_eq!(settings.version, SETTINGS_VERSION, "from version {}", version);
+            assert_eq!(settings.difficulty, Difficulty::Easy, "from version {}", version);
+        }
+    }
+
+    #[test]
+    fn settings_from_before_the_angled_bounce_keep_the_classic_one() {
+        assert_eq!(parse("(version: 2)").unwrap().bounce, Bounce::Classic);
+        assert_eq!(parse("()").unwrap().bounce, Bounce::Classic);
+        assert_eq!(parse("(version: 3)").unwrap().bounce, Bounce::default());
+        assert_eq!(parse("(version: 3, bounce: Classic)").unwrap().bounce, Bounce::Classic);
+    }
+
+    #[test]
+    fn newer_settings_keep_what_is_known() {
+        let settings = parse("(version: 99, difficulty: Hard, from_the_future: true)").unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.difficulty, Difficulty::Hard);
+    }
+
+    #[test]
+    fn broken_settings_are_an_error() {
+        assert!(parse("(difficulty: ").is_err());
+        assert!(parse("(version: \"three\")").is_err());
+    }
+}
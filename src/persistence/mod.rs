@@ -1,29 +1,197 @@
+pub mod migration;
+pub mod paths;
 pub mod window;
 
 use window::WindowSettings;
 use amethyst::window::ScreenDimensions;
-use amethyst::core::ecs::rayon::spawn_fifo;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use crate::simulation::ai::Difficulty;
+use crate::simulation::ball::{BallMode, Bounce};
+use crate::simulation::controller::Players;
+use crate::simulation::rng::clock_seed;
+use crate::simulation::rules::MatchRules;
 
-#[derive(Default, Debug, Serialize, Deserialize, Copy, Clone)]
+const SETTINGS_FILE: &str = "settings.ron";
+const MIN_WINDOW_WIDTH: f32 = 320.0;
+const MIN_WINDOW_HEIGHT: f32 = 240.0;
+const MIN_FIXED_STEP: f32 = 1.0 / 1000.0;
+const MAX_FIXED_STEP: f32 = 1.0 / 20.0;
+const MIN_FPS_CAP: u32 = 30;
+
+/// Where settings are read from and saved to, when launched with another file than the default one.
+static SETTINGS_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Settings {
+    /// The layout the settings were saved with, see `migration`.
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
     pub window_settings: WindowSettings,
+    #[serde(default)]
     pub audio_settings: AudioSettings,
+    #[serde(default)]
+    pub display_settings: DisplaySettings,
+    #[serde(default)]
+    pub physics_settings: PhysicsSettings,
+    #[serde(default)]
+    pub match_rules: MatchRules,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub players: Players,
+    #[serde(default)]
+    pub ball_mode: BallMode,
+    #[serde(default)]
+    pub bounce: Bounce,
+    /// File name of the level matches are played in, without the extension. The classic empty arena when `None`.
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Seeds every match with the same randomness to reproduce it, a new seed is picked per match when `None`.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// A seed given for this run only, used instead of `seed` and never saved.
+    #[serde(skip)]
+    pub seed_override: Option<u64>,
+    /// Settings given for this run only, the saved values are written back in their place.
+    #[serde(skip)]
+    pub overrides: Overrides,
+}
+
+/// The settings replaced for this run only, with the values they replaced.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Overrides {
+    window_settings: Option<Overridden<WindowSettings>>,
+    window_mode: Option<Overridden<WindowMode>>,
+    difficulty: Option<Overridden<Difficulty>>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Overridden<T> {
+    saved: T,
+    run: T,
+}
+
+impl<T: Copy + PartialEq> Overridden<T> {
+    fn between(saved: T, run: T) -> Option<Overridden<T>> {
+        (saved != run).then_some(Overridden { saved, run })
+    }
+
+    /// The value to save in place of `current`, which is only the override while it wasn't changed since.
+    fn to_save(overridden: Option<Overridden<T>>, current: T) -> T {
+        match overridden {
+            Some(Overridden { saved, run }) if run == current => saved,
+            _ => current,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: migration::SETTINGS_VERSION,
+            window_settings: WindowSettings::default(),
+            audio_settings: AudioSettings::default(),
+            display_settings: DisplaySettings::default(),
+            physics_settings: PhysicsSettings::default(),
+            match_rules: MatchRules::default(),
+            difficulty: Difficulty::default(),
+            players: Players::default(),
+            ball_mode: BallMode::default(),
+            bounce: Bounce::default(),
+            level: None,
+            seed: None,
+            seed_override: None,
+            overrides: Overrides::default(),
+        }
+    }
 }
 
 impl Settings {
+    /// Reads the settings saved last, or the bundled ones if they were never saved.
     pub fn read_or_default() -> Settings {
-        std::fs::read_to_string("config/settings.ron")
-            .ok()
-            .and_then(|content| ron::from_str::<'_, Settings>(&content).ok())
-            .unwrap_or_else(Settings::default)
+        let path = SETTINGS_PATH.get().cloned().unwrap_or_else(|| paths::config_file(SETTINGS_FILE));
+        paths::read_ron(&path, "settings", migration::parse)
+            .map(Settings::validated)
+            .unwrap_or_default()
+    }
+
+    /// Replaces values out of their range with the closest valid ones, or their default.
+    pub fn validated(mut self) -> Settings {
+        let window = &mut self.window_settings;
+        if !(window.width >= MIN_WINDOW_WIDTH && window.height >= MIN_WINDOW_HEIGHT) {
+            log::warn!("Window size {}x{} is too small, using at least {}x{}", window.width, window.height, MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT);
+            window.width = if window.width.is_nan() { MIN_WINDOW_WIDTH } else { window.width.max(MIN_WINDOW_WIDTH) };
+            window.height = if window.height.is_nan() { MIN_WINDOW_HEIGHT } else { window.height.max(MIN_WINDOW_HEIGHT) };
+        }
+        let audio = &mut self.audio_settings;
+        for (name, volume) in [("music_volume", &mut audio.music_volume), ("effects_volume", &mut audio.effects_volume)] {
+            if !(0.0..=1.0).contains(volume) {
+                let valid = if volume.is_nan() { 0.0 } else { volume.clamp(0.0, 1.0) };
+                log::warn!("{} {} is not between 0.0 and 1.0, using {}", name, volume, valid);
+                *volume = valid;
+            }
+        }
+        let fixed_step = &mut self.physics_settings.fixed_step;
+        if !(MIN_FIXED_STEP..=MAX_FIXED_STEP).contains(fixed_step) {
+            let valid = PhysicsSettings::default().fixed_step;
+            log::warn!("fixed_step {} is not between {} and {}, using {}", fixed_step, MIN_FIXED_STEP, MAX_FIXED_STEP, valid);
+            *fixed_step = valid;
+        }
+        let fps_cap = &mut self.display_settings.fps_cap;
+        if *fps_cap != 0 && *fps_cap < MIN_FPS_CAP {
+            log::warn!("fps_cap {} is below {}, using {}", fps_cap, MIN_FPS_CAP, MIN_FPS_CAP);
+            *fps_cap = MIN_FPS_CAP;
+        }
+        match &mut self.match_rules {
+            MatchRules::FirstTo { points, .. } if *points == 0 => {
+                log::warn!("FirstTo needs at least 1 point, using 1");
+                *points = 1;
+            }
+            MatchRules::TimeLimit { seconds } if !(*seconds > 0.0 && seconds.is_finite()) => {
+                log::warn!("TimeLimit of {} seconds is not positive, using Survival", seconds);
+                self.match_rules = MatchRules::Survival;
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Reads and saves settings in `path` from now on, instead of the default file.
+    pub fn use_path(path: &Path) {
+        if SETTINGS_PATH.set(path.to_path_buf()).is_err() {
+            log::warn!("Settings path is already set, ignoring {}", path.display());
+        }
+    }
+
+    /// The seed for the next match, picked from the clock unless one is configured.
+    pub fn match_seed(&self) -> u64 {
+        self.seed_override.or(self.seed).unwrap_or_else(clock_seed)
+    }
+
+    /// Changes the settings with `change` for this run only, they are saved with the values they had before.
+    pub fn override_for_run(&mut self, change: impl FnOnce(&mut Settings)) {
+        let saved = self.clone();
+        change(self);
+        self.overrides = Overrides {
+            window_settings: Overridden::between(saved.window_settings, self.window_settings),
+            window_mode: Overridden::between(saved.display_settings.window_mode, self.display_settings.window_mode),
+            difficulty: Overridden::between(saved.difficulty, self.difficulty),
+        };
+    }
+
+    /// The settings to save, without the ones overridden for this run.
+    pub(crate) fn to_save(&self) -> Settings {
+        let mut saved = self.clone();
+        saved.window_settings = Overridden::to_save(self.overrides.window_settings, self.window_settings);
+        saved.display_settings.window_mode = Overridden::to_save(self.overrides.window_mode, self.display_settings.window_mode);
+        saved.difficulty = Overridden::to_save(self.overrides.difficulty, self.difficulty);
+        saved
     }
 
     pub fn update_window(&mut self, dimensions: &ScreenDimensions) -> bool {
-        return if self.window_settings.height != dimensions.height() {
-            self.window_settings.height = dimensions.height();
-            self.window_settings.width = dimensions.width();
-            true
-        } else if self.window_settings.width != dimensions.width() {
+        if self.window_settings.height != dimensions.height() || self.window_settings.width != dimensions.width() {
             self.window_settings.height = dimensions.height();
             self.window_settings.width = dimensions.width();
             true
@@ -31,16 +199,13 @@ impl Settings {
     }
 
     pub fn persist_async(&self) {
-        let copy = *self;
-        spawn_fifo(move || {
-            if let Ok(content) = ron::to_string(&copy) {
-                std::fs::write("config/settings.ron", content).unwrap();
-            }
-        })
+        let path = SETTINGS_PATH.get().cloned().unwrap_or_else(|| paths::user_config_dir().join(SETTINGS_FILE));
+        paths::persist_ron_async(path, "settings", self.to_save());
     }
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AudioSettings {
     pub music_volume: f32,
     pub effects_volume: f32,
@@ -54,3 +219,114 @@ impl Default for AudioSettings {
         }
     }
 }
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    /// A window without decorations covering the whole monitor.
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowMode {
+    /// The next mode, wrapping around after `Fullscreen`.
+    pub fn next(self) -> WindowMode {
+        match self {
+            WindowMode::Windowed => WindowMode::Borderless,
+            WindowMode::Borderless => WindowMode::Fullscreen,
+            WindowMode::Fullscreen => WindowMode::Windowed,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WindowMode::Windowed => "WINDOWED",
+            WindowMode::Borderless => "BORDERLESS",
+            WindowMode::Fullscreen => "FULLSCREEN",
+        }
+    }
+}
+
+/// How the game window is shown. Frames are always presented in sync with the display, amethyst's
+/// renderer has no other present mode, so `fps_cap` only limits them further.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub window_mode: WindowMode,
+    /// Index of the monitor used when borderless or fullscreen, in the order the platform lists them.
+    pub monitor: usize,
+    /// Most frames drawn per second, 0 for no limit.
+    pub fps_cap: u32,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        DisplaySettings {
+            window_mode: WindowMode::Windowed,
+            monitor: 0,
+            fps_cap: 144,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsSettings {
+    /// Seconds simulated by each physics step, independent of the frame rate.
+    pub fixed_step: f32,
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        PhysicsSettings {
+            fixed_step: 1.0 / 120.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_settings_are_kept() {
+        let settings = Settings::default().validated();
+        let defaults = Settings::default();
+        assert_eq!(settings.window_settings, defaults.window_settings);
+        assert_eq!(settings.physics_settings.fixed_step, defaults.physics_settings.fixed_step);
+        assert_eq!(settings.display_settings.fps_cap, defaults.display_settings.fps_cap);
+        assert_eq!(settings.match_rules, defaults.match_rules);
+    }
+
+    #[test]
+    fn values_out_of_range_are_clamped() {
+        let settings = Settings {
+            window_settings: WindowSettings { width: 100.0, height: f32::NAN },
+            audio_settings: AudioSettings { music_volume: 3.0, effects_volume: -1.0 },
+            physics_settings: PhysicsSettings { fixed_step: 1.0 },
+            display_settings: DisplaySettings { fps_cap: 5, ..Default::default() },
+            ..Default::default()
+        }.validated();
+        assert_eq!(settings.window_settings, WindowSettings { width: MIN_WINDOW_WIDTH, height: MIN_WINDOW_HEIGHT });
+        assert_eq!(settings.audio_settings.music_volume, 1.0);
+        assert_eq!(settings.audio_settings.effects_volume, 0.0);
+        assert_eq!(settings.physics_settings.fixed_step, PhysicsSettings::default().fixed_step);
+        assert_eq!(settings.display_settings.fps_cap, MIN_FPS_CAP);
+    }
+
+    #[test]
+    fn uncapped_frame_rate_is_kept() {
+        let settings = Settings { display_settings: DisplaySettings { fps_cap: 0, ..Default::default() }, ..Default::default() };
+        assert_eq!(settings.validated().display_settings.fps_cap, 0);
+    }
+
+    #[test]
+    fn match_rules_that_never_end_or_end_at_once_are_replaced() {
+        let settings = Settings { match_rules: MatchRules::FirstTo { points: 0, win_by_two: true }, ..Default::default() };
+        assert_eq!(settings.validated().match_rules, MatchRules::FirstTo { points: 1, win_by_two: true });
+
+        let settings = Settings { match_rules: MatchRules::TimeLimit { seconds: -3.0 }, ..Default::default() };
+        assert_eq!(settings.validated().match_rules, MatchRules::Survival);
+    }
+}
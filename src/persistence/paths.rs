@@ -0,0 +1,130 @@
+use std::fmt::Display;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use amethyst::core::ecs::rayon::spawn_fifo;
+use amethyst::utils::application_root_dir;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Overrides the directory config files are read from and saved to.
+pub const CONFIG_DIR_ENV: &str = "IMPOSSI_PONG_CONFIG_DIR";
+
+const APP_DIR: &str = "impossi-pong";
+const BUNDLED_DIR: &str = "config";
+
+/// The directory the player's config files are saved to: `$IMPOSSI_PONG_CONFIG_DIR` when set,
+/// else the platform config directory, such as `~/.config/impossi-pong` on Linux.
+pub fn user_config_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(CONFIG_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_DIR))
+        .unwrap_or_else(bundled_config_dir)
+}
+
+/// The `config` directory shipped next to the game, holding the defaults.
+pub fn bundled_config_dir() -> PathBuf {
+    application_root_dir()
+        .map(|root| root.join(BUNDLED_DIR))
+        .unwrap_or_else(|_| PathBuf::from(BUNDLED_DIR))
+}
+
+/// Where the config file `name` is read from, the player's copy if it was saved before,
+/// else the bundled default.
+pub fn config_file(name: &str) -> PathBuf {
+    let user_file = user_config_dir().join(name);
+    if user_file.exists() {
+        user_file
+    } else {
+        bundled_config_dir().join(name)
+    }
+}
+
+/// Replaces `path` with `content` through a temporary file, so a crash while writing never
+/// leaves half a file behind.
+///
+/// Every write has a temporary file of its own, saves run on a thread pool and two saves of the
+/// same file may overlap.
+pub fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(format!(".{}-{}.tmp", std::process::id(), WRITES.fetch_add(1, Ordering::Relaxed)));
+    let written = std::fs::write(&temp, content).and_then(|_| std::fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    written
+}
+
+/// Reads the `what` saved in `path` with `parse`, `None` when it is missing or can't be parsed.
+///
+/// A file that can't be parsed is kept as `.bak` next to it, since it is replaced the next time
+/// it is saved.
+pub fn read_ron<T, E: Display>(path: &Path, what: &str, parse: impl FnOnce(&str) -> Result<T, E>) -> Option<T> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            log::warn!("Failed to read {} from {}: {}", what, path.display(), e);
+            return None;
+        }
+    };
+    match parse(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::warn!("Failed to parse {} in {}: {}", what, path.display(), e);
+            let mut backup = path.as_os_str().to_owned();
+            backup.push(".bak");
+            if let Err(e) = std::fs::copy(path, &backup) {
+                log::error!("Failed to back up {} to {:?}: {}", what, backup, e);
+            }
+            None
+        }
+    }
+}
+
+/// Reads the `what` saved in `path`, or its default when it is missing or can't be parsed.
+pub fn read_ron_or_default<T: DeserializeOwned + Default>(path: &Path, what: &str) -> T {
+    read_ron(path, what, |content| ron::from_str(content)).unwrap_or_default()
+}
+
+/// Saves `value` as the `what` in `path` on another thread, failures are only logged.
+pub fn persist_ron_async<T: Serialize + Send + 'static>(path: PathBuf, what: &'static str, value: T) {
+    spawn_fifo(move || {
+        let result = ron::ser::to_string_pretty(&value, Default::default())
+            .map_err(|e| e.to_string())
+            .and_then(|content| write_atomically(&path, &content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log::error!("Failed to save {} to {}: {}", what, path.display(), e);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_writes_each_leave_a_whole_file() {
+        let dir = std::env::temp_dir().join(format!("impossi-pong-writes-{}", std::process::id()));
+        let path = dir.join("settings.ron");
+        let contents: Vec<String> = (0..8).map(|index| index.to_string().repeat(10_000)).collect();
+        std::thread::scope(|scope| {
+            for content in contents.iter() {
+                let path = &path;
+                scope.spawn(move || write_atomically(path, content).unwrap());
+            }
+        });
+        let written = std::fs::read_to_string(&path).unwrap();
+        let leftovers = std::fs::read_dir(&dir).unwrap().count();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(contents.contains(&written));
+        assert_eq!(leftovers, 1);
+    }
+}
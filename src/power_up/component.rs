@@ -0,0 +1,12 @@
+use amethyst::core::ecs::{Component, DenseVecStorage};
+
+use crate::simulation::power_up::PowerUpId;
+
+/// Links an entity to its power-up in the `Simulation`.
+pub struct PowerUp {
+    pub id: PowerUpId,
+}
+
+impl Component for PowerUp {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,34 @@
+use amethyst::{
+    core::Hidden,
+    derive::SystemDesc,
+    ecs::{Entities, Join, Read, ReadStorage, System, SystemData, WriteStorage},
+};
+
+use crate::ball::component::Ball;
+use crate::simulation::Simulation;
+
+/// Hides the balls in the fog of a `Fog` power-up.
+#[derive(SystemDesc)]
+pub struct FogSystem;
+
+impl<'s> System<'s> for FogSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ball>,
+        WriteStorage<'s, Hidden>,
+        Read<'s, Simulation>,
+    );
+
+    fn run(&mut self, (entities, balls, mut hidden, simulation): Self::SystemData) {
+        for (entity, ball) in (&entities, &balls).join() {
+            let fogged = simulation.ball(ball.id).is_some_and(|state| simulation.is_fogged(state));
+            if fogged && !hidden.contains(entity) {
+                if let Err(err) = hidden.insert(entity, Hidden) {
+                    log::error!("Failed to hide a ball in the fog, err={}", err);
+                }
+            } else if !fogged {
+                hidden.remove(entity);
+            }
+        }
+    }
+}
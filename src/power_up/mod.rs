@@ -0,0 +1,19 @@
+pub mod component;
+pub mod power_up_system;
+pub mod fog_system;
+
+use crate::persistence::paths;
+use crate::simulation::power_up::{PowerUpKind, PowerUpRules};
+
+const POWER_UPS_FILE: &str = "power_ups.ron";
+/// The power-up sprites follow the taunt faces in the sheet, in the order of `PowerUpKind::ALL`.
+const FIRST_POWER_UP_SPRITE: usize = 5;
+
+/// Reads the spawn rules of power-ups, they stay disabled when the file is missing or broken.
+pub fn read_rules() -> PowerUpRules {
+    paths::read_ron_or_default(&paths::config_file(POWER_UPS_FILE), "power-ups")
+}
+
+pub fn sprite_number(kind: PowerUpKind) -> usize {
+    FIRST_POWER_UP_SPRITE + PowerUpKind::ALL.iter().position(|known| *known == kind).unwrap_or(0)
+}
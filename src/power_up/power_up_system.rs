@@ -0,0 +1,28 @@
+use amethyst::{
+    derive::SystemDesc,
+    ecs::{Read, System, SystemData, Write},
+};
+use amethyst::shrev::EventChannel;
+
+use crate::events::{publish, GameEvent};
+use crate::simulation::Simulation;
+use crate::state::Pause;
+
+/// Lets the balls collect the power-ups they passed through.
+#[derive(SystemDesc)]
+pub struct PowerUpSystem;
+
+impl<'s> System<'s> for PowerUpSystem {
+    type SystemData = (
+        Write<'s, Simulation>,
+        Read<'s, Pause>,
+        Write<'s, EventChannel<GameEvent>>,
+    );
+
+    fn run(&mut self, (mut simulation, pause, mut game_events): Self::SystemData) {
+        if pause.paused {
+            return;
+        }
+        publish(&mut game_events, simulation.collect_power_ups());
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use amethyst::core::ecs::rayon::spawn_fifo;
+
+use crate::persistence::paths;
+use crate::simulation::{Simulation, SimulationConfig, TickInput};
+
+/// Bumped whenever the format changes, or the simulation changes in a way old replays no
+/// longer play back the same.
+pub const REPLAY_VERSION: u32 = 5;
+
+const REPLAY_DIR: &str = "replays";
+const LAST_REPLAY_FILE: &str = "last.ron";
+
+/// Everything needed to play a match back tick by tick: the simulation it started from and the
+/// input of every tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    version: u32,
+    config: SimulationConfig,
+    /// Seconds simulated by each tick.
+    fixed_step: f32,
+    /// The input of every tick, with consecutive ticks of the same input stored once.
+    inputs: Vec<InputRun>,
+    /// Ticks after which a rally had just started, to jump between rallies.
+    #[serde(default)]
+    rally_starts: Vec<u32>,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+struct InputRun {
+    ticks: u32,
+    input: TickInput,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Format(ron::Error),
+    /// The replay was recorded by a version of the game that plays differently.
+    Version(u32),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "Failed to access replay: {}", e),
+            ReplayError::Format(e) => write!(f, "Failed to read replay: {}", e),
+            ReplayError::Version(version) => write!(f, "Replay has version {}, only version {} can be played", version, REPLAY_VERSION),
+        }
+    }
+}
+
+/// Only the version of a replay, read first so replays of other versions are reported as such.
+#[derive(Deserialize)]
+struct ReplayVersion {
+    version: u32,
+}
+
+impl Replay {
+    pub fn new(config: SimulationConfig, fixed_step: f32) -> Self {
+        Replay {
+            version: REPLAY_VERSION,
+            config,
+            fixed_step,
+            inputs: vec![],
+            rally_starts: vec![],
+        }
+    }
+
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    pub fn fixed_step(&self) -> f32 {
+        self.fixed_step
+    }
+
+    /// Number of ticks recorded.
+    pub fn len(&self) -> u32 {
+        self.inputs.iter().map(|run| run.ticks).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Adds the input of the next tick.
+    pub fn record(&mut self, input: TickInput) {
+        match self.inputs.last_mut() {
+            Some(run) if run.input == input => run.ticks += 1,
+            _ => self.inputs.push(InputRun { ticks: 1, input }),
+        }
+    }
+
+    /// Records that a rally started in the last tick recorded.
+    pub fn record_rally_start(&mut self) {
+        self.rally_starts.push(self.len());
+    }
+
+    /// The tick the last rally starting before `tick` started on.
+    pub fn previous_rally(&self, tick: u32) -> Option<u32> {
+        self.rally_starts.iter().rev().find(|start| **start < tick).copied()
+    }
+
+    /// The tick the first rally starting after `tick` starts on.
+    pub fn next_rally(&self, tick: u32) -> Option<u32> {
+        self.rally_starts.iter().find(|start| **start > tick).copied()
+    }
+
+    /// The input of `tick`, or `None` past the end of the replay.
+    pub fn input(&self, tick: u32) -> Option<TickInput> {
+        let mut start = 0;
+        for run in self.inputs.iter() {
+            if tick < start + run.ticks {
+                return Some(run.input);
+            }
+            start += run.ticks;
+        }
+        None
+    }
+
+    /// Simulates the first `ticks` ticks without any window, used to jump around in a replay.
+    pub fn simulate(&self, ticks: u32) -> Simulation {
+        let mut simulation = Simulation::new(self.config.clone());
+        for tick in 0..ticks.min(self.len()) {
+            let input = self.input(tick).unwrap_or_default();
+            simulation.step(self.fixed_step, &input);
+        }
+        simulation
+    }
+
+    /// Saves the replay as the last one played, overwriting the one before it.
+    pub fn save_async(&self) {
+        let copy = self.clone();
+        spawn_fifo(move || {
+            if let Err(e) = copy.save(&last_replay_path()) {
+                log::error!("{}", e);
+            }
+        })
+    }
+
+    pub fn load_last() -> Result<Replay, ReplayError> {
+        Replay::load(&last_replay_path())
+    }
+
+    fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        let content = ron::to_string(self).map_err(ReplayError::Format)?;
+        paths::write_atomically(path, &content).map_err(ReplayError::Io)
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, ReplayError> {
+        let content = std::fs::read_to_string(path).map_err(ReplayError::Io)?;
+        let version = ron::from_str::<ReplayVersion>(&content).map_err(ReplayError::Format)?.version;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::Version(version));
+        }
+        ron::from_str(&content).map_err(ReplayError::Format)
+    }
+}
+
+/// Where the last match played is saved, in the `replays` directory next to the settings.
+pub fn last_replay_path() -> PathBuf {
+    paths::user_config_dir().join(REPLAY_DIR).join(LAST_REPLAY_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: f32 = 1.0 / 120.0;
+
+    /// Steps a match between the AI and a player whose input follows the tick, recording it as it goes.
+    fn play_recorded(config: SimulationConfig, ticks: u32) -> (Simulation, Replay) {
+        let mut simulation = Simulation::new(config.clone());
+        let mut replay = Replay::new(config, STEP);
+        for tick in 0..ticks {
+            let input = TickInput { left_paddle: ((tick / 90) as f32).sin(), right_paddle: 0.0 };
+            replay.record(input);
+            simulation.step(STEP, &input);
+        }
+        (simulation, replay)
+    }
+
+    /// A file in the temp directory only this test uses.
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("impossi-pong-{}-{}.ron", std::process::id(), name))
+    }
+
+    #[test]
+    fn saved_replay_plays_back_the_recorded_match() {
+        let config = SimulationConfig { seed: 42, ..SimulationConfig::default() };
+        let (live, replay) = play_recorded(config, 120 * 30);
+        // Held inputs are stored once per run.
+        assert!(replay.inputs.len() < replay.len() as usize);
+
+        let path = temp_file("replay");
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.len(), replay.len());
+        assert_eq!(format!("{:?}", loaded.simulate(loaded.len())), format!("{:?}", live));
+    }
+
+    #[test]
+    fn replay_of_another_version_is_refused() {
+        let (_, mut replay) = play_recorded(SimulationConfig::default(), 10);
+        replay.version = REPLAY_VERSION - 1;
+        let path = temp_file("old-replay");
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(loaded, Err(ReplayError::Version(version)) if version == REPLAY_VERSION - 1));
+    }
+
+    #[test]
+    fn rallies_are_found_around_a_tick() {
+        let (_, mut replay) = play_recorded(SimulationConfig::default(), 10);
+        replay.rally_starts = vec![3, 7];
+        assert_eq!(replay.previous_rally(7), Some(3));
+        assert_eq!(replay.next_rally(3), Some(7));
+        assert_eq!(replay.next_rally(7), None);
+        assert_eq!(replay.input(10), None);
+    }
+}
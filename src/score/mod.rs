@@ -0,0 +1,8 @@
+pub mod score_system;
+
+use amethyst::core::ecs::Entity;
+
+#[derive(Default)]
+pub struct ScoreText {
+    pub score: Option<Entity>,
+}
@@ -0,0 +1,34 @@
+use amethyst::core::ecs::{Read, System, WriteStorage};
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::SystemData;
+use amethyst::ui::UiText;
+
+use crate::score::ScoreText;
+use crate::simulation::Simulation;
+use crate::simulation::rules::Score;
+
+#[derive(SystemDesc)]
+pub struct ScoreSystem;
+
+impl<'s> System<'s> for ScoreSystem {
+    type SystemData = (
+        Read<'s, Simulation>,
+        Read<'s, ScoreText>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (simulation, score_text, mut ui_text): Self::SystemData) {
+        if let Some(score) = score_text.score {
+            if let Some(ui) = ui_text.get_mut(score) {
+                let text = format_score(simulation.score());
+                if ui.text != text {
+                    ui.text = text;
+                }
+            }
+        }
+    }
+}
+
+pub fn format_score(score: &Score) -> String {
+    format!("{} - {}", score.left, score.right)
+}
@@ -0,0 +1,311 @@
+use rand::Rng;
+
+use crate::simulation::arena::Arena;
+use crate::simulation::ball::BallState;
+use crate::simulation::paddle::{PaddleState, calc_to_move, clamp_to_arena};
+use crate::simulation::rng::GameRng;
+
+/// The AI presets that can be picked from the main menu.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    #[default]
+    Impossible,
+}
+
+impl Difficulty {
+    pub fn profile(self) -> AiProfile {
+        match self {
+            Difficulty::Easy => AiProfile {
+                reaction_delay: 0.35,
+                prediction_error: 1.5,
+                max_speed_fraction: 0.55,
+                returns_to_center: false,
+            },
+            Difficulty::Normal => AiProfile {
+                reaction_delay: 0.2,
+                prediction_error: 0.8,
+                max_speed_fraction: 0.75,
+                returns_to_center: true,
+            },
+            Difficulty::Hard => AiProfile {
+                reaction_delay: 0.1,
+                prediction_error: 0.35,
+                max_speed_fraction: 0.9,
+                returns_to_center: true,
+            },
+            Difficulty::Impossible => AiProfile {
+                reaction_delay: 0.0,
+                prediction_error: 0.0,
+                max_speed_fraction: 1.0,
+                returns_to_center: true,
+            },
+        }
+    }
+
+    /// The next preset, wrapping around after `Impossible`.
+    pub fn next(self) -> Difficulty {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Impossible,
+            Difficulty::Impossible => Difficulty::Easy,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "EASY",
+            Difficulty::Normal => "NORMAL",
+            Difficulty::Hard => "HARD",
+            Difficulty::Impossible => "IMPOSSIBLE",
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = ();
+
+    /// Parses the name of a preset, ignoring case.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let mut difficulty = Difficulty::Easy;
+        loop {
+            if difficulty.name().eq_ignore_ascii_case(name) {
+                return Ok(difficulty);
+            }
+            difficulty = difficulty.next();
+            if difficulty == Difficulty::Easy {
+                return Err(());
+            }
+        }
+    }
+}
+
+/// How well an AI plays.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AiProfile {
+    /// Seconds the AI keeps to its old plan after the ball's predicted impact changes.
+    pub reaction_delay: f32,
+    /// Largest error in where the AI aims, in paddle heights.
+    pub prediction_error: f32,
+    /// Fraction of the paddle speed the AI moves at.
+    pub max_speed_fraction: f32,
+    /// Whether the AI moves back to the middle while the ball heads away from it.
+    pub returns_to_center: bool,
+}
+
+impl Default for AiProfile {
+    fn default() -> Self {
+        Difficulty::default().profile()
+    }
+}
+
+/// Moves a paddle towards the predicted impact of the ball, as well as its profile allows.
+#[derive(Debug, Clone)]
+pub struct Ai {
+    profile: AiProfile,
+    seen_impact: Option<f32>,
+    /// Where the paddle is heading now.
+    target: Option<f32>,
+    /// Where the paddle heads once it reacted to the last change of the impact.
+    next_target: Option<f32>,
+    reaction_left: f32,
+}
+
+impl Ai {
+    pub fn new(profile: AiProfile) -> Self {
+        Ai {
+            profile,
+            seen_impact: None,
+            target: None,
+            next_target: None,
+            reaction_left: 0.0,
+        }
+    }
+
+    pub fn steer(&mut self, paddle: &mut PaddleState, balls: &[BallState], dt: f32, arena: &Arena, rng: &mut GameRng) {
+        // Defend against the ball that reaches the goal first.
+        let impact = balls.iter()
+            .filter_map(|ball| ball.time_to_goal(paddle.side, arena).map(|time| (time, ball)))
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .and_then(|(_, ball)| ball.calculated_impact_y);
+        if impact != self.seen_impact {
+            self.seen_impact = impact;
+            self.reaction_left = self.profile.reaction_delay;
+            let error = if self.profile.prediction_error > 0.0 {
+                rng.gen_range(-self.profile.prediction_error..self.profile.prediction_error) * paddle.height
+            } else { 0.0 };
+            self.next_target = impact.map(|impact| impact + error);
+        }
+        if self.reaction_left > 0.0 {
+            self.reaction_left -= dt;
+        } else {
+            self.target = self.next_target;
+        }
+
+        let max_move = arena.paddle_speed() * self.profile.max_speed_fraction * dt;
+        let paddle_y = paddle.y;
+        if let Some(target) = self.target {
+            let distance = (target - paddle_y - paddle.height * 0.5).abs();
+            if target > paddle_y + paddle.height * 0.5 {
+                paddle.y = paddle_y + calc_to_move(distance, max_move);
+            } else if target < paddle_y - paddle.height * 0.5 {
+                paddle.y = paddle_y - calc_to_move(distance, max_move);
+            }
+        } else if self.profile.returns_to_center && balls.iter().any(|ball| ball.is_heading_to(paddle.side.opponent())) {
+            // Move towards middle
+            let center = arena.height() / 2.0;
+            let distance = (center - paddle_y).abs();
+            if distance < 2.0 {
+                paddle.y = center;
+            } else if paddle_y < center {
+                paddle.y = paddle_y + calc_to_move(distance, max_move);
+            } else if paddle_y > center {
+                paddle.y = paddle_y - calc_to_move(distance, max_move);
+            }
+        }
+        paddle.y = clamp_to_arena(paddle.y, paddle.height, arena);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::arena::ARENA;
+    use crate::simulation::controller::Players;
+    use crate::simulation::paddle::Side;
+    use crate::simulation::{SimEvent, Simulation, SimulationConfig, TickInput};
+
+    const STEP: f32 = 1.0 / 120.0;
+    const DIFFICULTIES: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Impossible];
+
+    /// A ball heading for the right paddle, predicted to reach it at `impact`.
+    fn ball_to_the_right(impact: Option<f32>) -> BallState {
+        BallState {
+            id: 0,
+            position: [800.0, 450.0],
+            previous_position: [800.0, 450.0],
+            velocity: [300.0, 0.0],
+            radius: ARENA.ball_radius(),
+            calculated_impact_y: impact,
+            spin: 0.0,
+            teleported: false,
+        }
+    }
+
+    /// Steers for `seconds`, checking the paddle never moves faster than the profile allows.
+    fn steer_for(ai: &mut Ai, paddle: &mut PaddleState, ball: &BallState, seconds: f32, rng: &mut GameRng) {
+        let max_move = ARENA.paddle_speed() * ai.profile.max_speed_fraction * STEP + 1e-3;
+        for _ in 0..(seconds / STEP).round() as u32 {
+            let before = paddle.y;
+            ai.steer(paddle, &[*ball], STEP, &ARENA, rng);
+            assert!((paddle.y - before).abs() <= max_move, "moved {} in a tick", paddle.y - before);
+        }
+    }
+
+    #[test]
+    fn presets_keep_their_old_plan_while_reacting() {
+        for difficulty in DIFFICULTIES {
+            let profile = difficulty.profile();
+            let mut ai = Ai::new(profile);
+            let mut rng = GameRng::new(3);
+            let mut paddle = PaddleState::new(Side::Right, &ARENA);
+            // Heading down for a low impact.
+            steer_for(&mut ai, &mut paddle, &ball_to_the_right(Some(100.0)), profile.reaction_delay + 0.1, &mut rng);
+            assert!(paddle.y < ARENA.height() / 2.0, "{:?} didn't move", difficulty);
+
+            // The impact moves to the top, the paddle only turns around once it reacted.
+            let high = ball_to_the_right(Some(800.0));
+            let mut reacted_after = 0.0;
+            let (at_change, mut lowest) = (paddle.y, paddle.y);
+            let mut before = paddle.y;
+            while paddle.y <= before {
+                before = paddle.y;
+                steer_for(&mut ai, &mut paddle, &high, STEP, &mut rng);
+                reacted_after += STEP;
+                lowest = lowest.min(paddle.y);
+                assert!(reacted_after < 1.0, "{:?} never turned around", difficulty);
+            }
+            if profile.reaction_delay > 0.0 {
+                assert!(lowest < at_change, "{:?} stopped instead of keeping to its plan", difficulty);
+            }
+            assert!((reacted_after - profile.reaction_delay - STEP).abs() < STEP * 1.5, "{:?} reacted after {}", difficulty, reacted_after);
+        }
+    }
+
+    #[test]
+    fn presets_aim_within_their_prediction_error() {
+        for difficulty in DIFFICULTIES {
+            let profile = difficulty.profile();
+            for seed in 0..20 {
+                let mut ai = Ai::new(profile);
+                let mut rng = GameRng::new(seed);
+                let mut paddle = PaddleState::new(Side::Right, &ARENA);
+                steer_for(&mut ai, &mut paddle, &ball_to_the_right(Some(300.0)), 3.0, &mut rng);
+                let reach = paddle.height * (0.5 + profile.prediction_error);
+                assert!((paddle.y - 300.0).abs() <= reach + 1e-3, "{:?} stopped at {}", difficulty, paddle.y);
+            }
+        }
+    }
+
+    #[test]
+    fn only_presets_that_return_to_center_do() {
+        for difficulty in DIFFICULTIES {
+            let mut ai = Ai::new(difficulty.profile());
+            let mut rng = GameRng::new(0);
+            let mut paddle = PaddleState::new(Side::Right, &ARENA);
+            paddle.y = 200.0;
+            let mut leaving = ball_to_the_right(None);
+            leaving.velocity[0] = -300.0;
+            steer_for(&mut ai, &mut paddle, &leaving, 3.0, &mut rng);
+            let centered = paddle.y == ARENA.height() / 2.0;
+            assert_eq!(centered, difficulty.profile().returns_to_center, "{:?} ended at {}", difficulty, paddle.y);
+        }
+    }
+
+    #[test]
+    fn defends_the_ball_that_reaches_the_goal_first() {
+        let mut ai = Ai::new(Difficulty::Impossible.profile());
+        let mut paddle = PaddleState::new(Side::Right, &ARENA);
+        // Listed first but further away, and slower.
+        let far = BallState { position: [200.0, 450.0], ..ball_to_the_right(Some(100.0)) };
+        let near = BallState { id: 1, position: [1200.0, 450.0], velocity: [600.0, 0.0], ..ball_to_the_right(Some(800.0)) };
+        ai.steer(&mut paddle, &[far, near], STEP, &ARENA, &mut GameRng::new(0));
+        assert_eq!(ai.target, Some(800.0));
+        assert!(paddle.y > ARENA.height() / 2.0);
+    }
+
+    #[test]
+    fn impossible_meets_every_ball() {
+        let arena = ARENA;
+        for height in [0.1, 0.3, 0.5, 0.7, 0.9] {
+            for (vx, vy) in [(1.0, 0.0), (1.0, 1.0), (1.0, -1.0), (2.0, 1.5), (2.0, -1.5)] {
+                let mut simulation = Simulation::new(SimulationConfig {
+                    controllers: Players::OnePlayer.controllers(Difficulty::Impossible),
+                    ..SimulationConfig::default()
+                });
+                simulation.serve_timer = None;
+                simulation.balls.clear();
+                simulation.spawn_ball([arena.ball_velocity_x() * vx, arena.ball_velocity_y() * vy]);
+                simulation.balls[0].position = [arena.width() / 2.0, arena.height() * height];
+                simulation.balls[0].previous_position = simulation.balls[0].position;
+
+                let mut met = None;
+                for _ in 0..(10.0 / STEP) as u32 {
+                    let events = simulation.step(STEP, &TickInput::default());
+                    met = events.iter().find_map(|event| match event {
+                        SimEvent::PaddleHit { side: Side::Right, .. } => Some(true),
+                        SimEvent::Goal { side: Side::Right } => Some(false),
+                        _ => None,
+                    });
+                    if met.is_some() {
+                        break;
+                    }
+                }
+                assert_eq!(met, Some(true), "missed the ball from {} at ({}, {})", height, vx, vy);
+            }
+        }
+    }
+}
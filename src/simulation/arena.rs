@@ -0,0 +1,100 @@
+const PADDLE_SPRITE_WIDTH: f32 = 16f32;
+const PADDLE_SPRITE_HEIGHT: f32 = 64f32;
+const BALL_SPRITE_WIDTH: f32 = 25f32;
+const TAUNT_SPRITE_WIDTH: f32 = 256f32;
+const POWER_UP_SPRITE_WIDTH: f32 = 32f32;
+const OBSTACLE_SPRITE_WIDTH: f32 = 32f32;
+
+/// The field the game is played on, in logical units rather than pixels.
+///
+/// Every gameplay size and speed is derived from it, and the camera fits it into the window,
+/// so matches play the same whatever the size of the window.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Arena {
+    width: f32,
+    height: f32,
+}
+
+pub const ARENA: Arena = Arena {
+    width: 1600.0,
+    height: 900.0,
+};
+
+impl Default for Arena {
+    fn default() -> Self {
+        ARENA
+    }
+}
+
+impl Arena {
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn ball_radius(&self) -> f32 {
+        self.width * 0.02
+    }
+
+    pub fn ball_velocity_x(&self) -> f32 {
+        self.width * 0.5
+    }
+
+    pub fn ball_velocity_y(&self) -> f32 {
+        self.width * 0.3
+    }
+
+    pub fn power_up_radius(&self) -> f32 {
+        self.width * 0.02
+    }
+
+    pub fn paddle_width(&self) -> f32 {
+        self.paddle_height() / 5.0
+    }
+
+    pub fn paddle_height(&self) -> f32 {
+        self.height * 0.15
+    }
+
+    pub fn paddle_speed(&self) -> f32 {
+        self.height * 0.75
+    }
+
+    pub fn paddle_width_scale(&self) -> f32 {
+        self.paddle_width() / PADDLE_SPRITE_WIDTH
+    }
+
+    pub fn paddle_height_scale(&self) -> f32 {
+        self.paddle_height() / PADDLE_SPRITE_HEIGHT
+    }
+
+
+    pub fn ball_scale(&self) -> f32 {
+        self.ball_radius() / BALL_SPRITE_WIDTH
+    }
+
+    pub fn power_up_scale(&self) -> f32 {
+        self.power_up_radius() * 2.0 / POWER_UP_SPRITE_WIDTH
+    }
+
+    /// Scale of an obstacle sprite stretched to `size`.
+    pub fn obstacle_scale(&self, size: [f32; 2]) -> [f32; 2] {
+        [size[0] / OBSTACLE_SPRITE_WIDTH, size[1] / OBSTACLE_SPRITE_WIDTH]
+    }
+
+    pub fn taunt_scale(&self) -> f32 {
+        self.width / (TAUNT_SPRITE_WIDTH * 12.5)
+    }
+
+    pub fn taunt_height(&self) -> f32 {
+        TAUNT_SPRITE_WIDTH * self.taunt_scale()
+    }
+
+
+    pub fn max_velocity(&self) -> f32 {
+        (self.width + 2.0 * self.paddle_width()) * 2.0 * self.paddle_speed() / self.height
+    }
+}
@@ -0,0 +1,274 @@
+use crate::simulation::arena::Arena;
+use crate::simulation::paddle::{PaddleState, Side};
+
+pub type BallId = u32;
+
+/// How many balls are in play.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BallMode {
+    #[default]
+    Single,
+    /// Extra balls join the rally on a schedule, and goals only take out the scoring ball.
+    Multi,
+}
+
+impl BallMode {
+    pub fn next(self) -> BallMode {
+        match self {
+            BallMode::Single => BallMode::Multi,
+            BallMode::Multi => BallMode::Single,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BallMode::Single => "SINGLE",
+            BallMode::Multi => "MULTI",
+        }
+    }
+}
+
+/// How balls leave a paddle.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Bounce {
+    /// The ball keeps its angle and only turns around, like the original game.
+    Classic,
+    /// The further from the middle of the paddle the ball hits, the steeper it leaves, up to
+    /// `max_angle` degrees. With `spin` a moving paddle also sends the ball curving that way.
+    Angled { max_angle: f32, spin: bool },
+}
+
+impl Default for Bounce {
+    fn default() -> Self {
+        Bounce::Angled { max_angle: 45.0, spin: true }
+    }
+}
+
+/// How much faster a ball gets with every paddle hit, as a fraction of its serve speed.
+pub const SPEED_INCREMENT: f32 = 0.035;
+/// Steepest angle in degrees a ball can leave a paddle at, whatever `Bounce::Angled` asks for.
+pub const MAX_BOUNCE_ANGLE: f32 = 75.0;
+/// Vertical acceleration a ball picks up per unit of vertical paddle speed when it is hit.
+pub const SPIN_TRANSFER: f32 = 0.75;
+/// How fast spin wears off, the fraction lost per second grows as `1 - e^(-SPIN_DECAY)`.
+pub const SPIN_DECAY: f32 = 1.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct BallState {
+    pub id: BallId,
+    pub position: [f32; 2],
+    /// Where the ball was before it last moved, the ball travelled in a line from here.
+    pub previous_position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub radius: f32,
+    pub calculated_impact_y: Option<f32>,
+    /// Vertical acceleration curving the ball, it wears off over time.
+    pub spin: f32,
+    /// Set when the ball was moved without travelling this tick, such as a reset after a goal.
+    pub teleported: bool,
+}
+
+impl BallState {
+    /// Whether the ball is moving towards the goal on `side`.
+    pub fn is_heading_to(&self, side: Side) -> bool {
+        match side {
+            Side::Left => self.velocity[0] < 0.0,
+            Side::Right => self.velocity[0] > 0.0,
+        }
+    }
+
+    /// How far the ball moves vertically in `dt` seconds and its vertical velocity after, as its spin
+    /// curves it. Spin wears off exponentially, so both follow in closed form from the current state.
+    pub fn drift(&self, dt: f32) -> (f32, f32) {
+        let spun_for = (1.0 - (-SPIN_DECAY * dt).exp()) / SPIN_DECAY;
+        let distance = self.velocity[1] * dt + self.spin / SPIN_DECAY * (dt - spun_for);
+        (distance, self.velocity[1] + self.spin * spun_for)
+    }
+
+    /// Moves the ball along its path for `dt` seconds, remembering where it came from.
+    pub(crate) fn advance(&mut self, dt: f32) {
+        self.previous_position = self.position;
+        let (distance_y, velocity_y) = self.drift(dt);
+        self.position[0] += self.velocity[0] * dt;
+        self.position[1] += distance_y;
+        self.velocity[1] = velocity_y;
+        self.spin *= (-SPIN_DECAY * dt).exp();
+    }
+
+    /// The goal the ball is in, once it reached either edge of the arena.
+    pub fn goal(&self, arena: &Arena) -> Option<Side> {
+        if self.position[0] <= self.radius {
+            Some(Side::Left)
+        } else if self.position[0] >= arena.width() - self.radius {
+            Some(Side::Right)
+        } else {
+            None
+        }
+    }
+
+    /// Seconds until the ball reaches the goal on `side` at its current speed, `None` when it moves away from it.
+    pub fn time_to_goal(&self, side: Side, arena: &Arena) -> Option<f32> {
+        if !self.is_heading_to(side) {
+            return None;
+        }
+        let distance = match side {
+            Side::Left => self.position[0],
+            Side::Right => arena.width() - self.position[0],
+        };
+        Some(distance / self.velocity[0].abs())
+    }
+}
+
+impl BallState {
+    /// Sends the ball back the way it came after a paddle hit, speeding it up until it nears
+    /// `Arena::max_velocity`. With `Bounce::Angled` it leaves at the angle of where it hit `paddle`.
+    pub(crate) fn return_from_paddle(&mut self, paddle: &PaddleState, bounce: Bounce, arena: &Arena) {
+        let mut speed_mod = 0.0;
+        if self.velocity[0].abs() + arena.ball_velocity_x() * 0.1 < arena.max_velocity() - arena.ball_velocity_x() * 0.1 {
+            speed_mod = SPEED_INCREMENT;
+        }
+        if self.velocity[0].is_sign_positive() {
+            self.velocity[0] = -self.velocity[0] - arena.ball_velocity_x() * speed_mod;
+        } else {
+            self.velocity[0] = -self.velocity[0] + arena.ball_velocity_x() * speed_mod;
+        }
+        if self.velocity[1].is_sign_positive() {
+            self.velocity[1] += arena.ball_velocity_y() * speed_mod;
+        } else {
+            self.velocity[1] -= arena.ball_velocity_y() * speed_mod;
+        }
+        if let Bounce::Angled { max_angle, spin } = bounce {
+            let offset = ((self.position[1] - paddle.y) / (paddle.height * 0.5 + self.radius)).clamp(-1.0, 1.0);
+            let angle = offset * max_angle.clamp(0.0, MAX_BOUNCE_ANGLE).to_radians();
+            self.velocity[1] = self.velocity[0].abs() * angle.tan();
+            self.spin = if spin { paddle.velocity * SPIN_TRANSFER } else { 0.0 };
+        }
+        self.calculated_impact_y = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::level::Level;
+    use crate::simulation::trajectory::{predict, PredictionLimits, Surface, Surroundings};
+
+    const STEP: f32 = 1.0 / 120.0;
+
+    fn ball_at(position: [f32; 2], velocity: [f32; 2], arena: &Arena) -> BallState {
+        BallState {
+            id: 0,
+            position,
+            previous_position: position,
+            velocity,
+            radius: arena.ball_radius(),
+            calculated_impact_y: None,
+            spin: 0.0,
+            teleported: false,
+        }
+    }
+
+    /// A ball that hits the right paddle `offset` of the way from its middle to its end.
+    fn hit_right_paddle(offset: f32, paddle_velocity: f32, bounce: Bounce) -> BallState {
+        let arena = Arena::default();
+        let mut paddle = PaddleState::new(Side::Right, &arena);
+        paddle.velocity = paddle_velocity;
+        let mut ball = ball_at([paddle.x, paddle.y], [arena.ball_velocity_x(), 0.0], &arena);
+        ball.position[1] += offset * (paddle.height * 0.5 + ball.radius);
+        ball.return_from_paddle(&paddle, bounce, &arena);
+        ball
+    }
+
+    fn leaving_angle(ball: &BallState) -> f32 {
+        (ball.velocity[1] / ball.velocity[0].abs()).atan().to_degrees()
+    }
+
+    #[test]
+    fn bounce_defaults_to_angles_with_spin() {
+        assert_eq!(Bounce::default(), Bounce::Angled { max_angle: 45.0, spin: true });
+    }
+
+    #[test]
+    fn ball_leaves_steeper_the_further_from_the_middle_it_hits() {
+        let bounce = Bounce::Angled { max_angle: 45.0, spin: false };
+        assert!(leaving_angle(&hit_right_paddle(0.0, 0.0, bounce)).abs() < 1e-3);
+        assert!((leaving_angle(&hit_right_paddle(0.5, 0.0, bounce)) - 22.5).abs() < 1e-3);
+        assert!((leaving_angle(&hit_right_paddle(-1.0, 0.0, bounce)) + 45.0).abs() < 1e-3);
+        // Hits past the end of the paddle leave like ones at its end.
+        assert!((leaving_angle(&hit_right_paddle(1.2, 0.0, bounce)) - 45.0).abs() < 1e-3);
+        assert!(hit_right_paddle(0.5, 0.0, bounce).velocity[0] < 0.0);
+    }
+
+    #[test]
+    fn bounce_angle_is_capped_at_the_steepest_allowed() {
+        let bounce = Bounce::Angled { max_angle: 89.0, spin: false };
+        assert!((leaving_angle(&hit_right_paddle(1.0, 0.0, bounce)) - MAX_BOUNCE_ANGLE).abs() < 1e-3);
+    }
+
+    #[test]
+    fn classic_bounce_keeps_the_angle() {
+        let arena = Arena::default();
+        let paddle = PaddleState::new(Side::Right, &arena);
+        let mut ball = ball_at([paddle.x, paddle.y + paddle.height * 0.4], [arena.ball_velocity_x(), arena.ball_velocity_y()], &arena);
+        ball.return_from_paddle(&paddle, Bounce::Classic, &arena);
+        assert!(ball.velocity[0] < 0.0);
+        assert!(ball.velocity[1] > arena.ball_velocity_y());
+        assert_eq!(ball.spin, 0.0);
+    }
+
+    #[test]
+    fn moving_paddle_spins_the_ball_only_with_spin() {
+        let spun = hit_right_paddle(0.0, 200.0, Bounce::Angled { max_angle: 45.0, spin: true });
+        assert_eq!(spun.spin, 200.0 * SPIN_TRANSFER);
+        let plain = hit_right_paddle(0.0, 200.0, Bounce::Angled { max_angle: 45.0, spin: false });
+        assert_eq!(plain.spin, 0.0);
+    }
+
+    #[test]
+    fn spin_curves_the_ball_and_wears_off() {
+        let arena = Arena::default();
+        let mut ball = ball_at([arena.width() / 2.0, arena.height() / 2.0], [arena.ball_velocity_x(), 0.0], &arena);
+        ball.spin = 100.0;
+        let mut last_velocity = 0.0;
+        for _ in 0..120 {
+            ball.advance(STEP);
+            assert!(ball.velocity[1] > last_velocity, "the ball stopped curving");
+            last_velocity = ball.velocity[1];
+        }
+        assert!(ball.position[1] > arena.height() / 2.0);
+        assert!((ball.spin - 100.0 * (-SPIN_DECAY).exp()).abs() < 1e-2);
+        // The vertical speed the ball gained is the spin it lost on the way.
+        assert!((ball.velocity[1] - 100.0 * (1.0 - (-SPIN_DECAY).exp()) / SPIN_DECAY).abs() < 1e-2);
+    }
+
+    #[test]
+    fn curved_path_is_predicted() {
+        let arena = Arena::default();
+        let level = Level::default();
+        let obstacles = level.obstacles(&arena);
+        let surroundings = Surroundings {
+            arena,
+            paddles: &[],
+            obstacles: &obstacles,
+            level: &level,
+            bounce: Bounce::default(),
+            elapsed: 0.0,
+            step: STEP,
+            time_scale: 1.0,
+        };
+        let start = [arena.width() / 2.0, arena.height() / 2.0];
+        let mut ball = ball_at(start, [arena.ball_velocity_x(), 0.0], &arena);
+        ball.spin = 60.0;
+
+        let goal = predict(&ball, &surroundings, PredictionLimits { step: STEP, ..Default::default() })
+            .into_iter()
+            .find(|contact| contact.surface == Surface::Goal(Side::Right))
+            .expect("the ball reaches the right goal");
+
+        while ball.goal(&arena).is_none() {
+            ball.advance(STEP);
+        }
+        assert!(goal.point[1] > start[1] + 10.0, "the prediction went straight to {:?}", goal.point);
+        assert!((goal.point[1] - ball.position[1]).abs() <= ball.velocity[1] * STEP);
+    }
+}
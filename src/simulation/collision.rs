@@ -0,0 +1,126 @@
+/// Where a circle moving along a segment first touches a surface.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Contact {
+    /// Fraction of the segment travelled before touching, from 0.0 to 1.0.
+    pub time: f32,
+    /// Unit normal of the touched surface, pointing back towards the circle.
+    pub normal: [f32; 2],
+    /// Center of the circle at the moment of contact.
+    pub point: [f32; 2],
+}
+
+impl Contact {
+    /// Whether something moving with `velocity` is heading into the touched surface.
+    pub fn is_approached_by(&self, velocity: [f32; 2]) -> bool {
+        velocity[0] * self.normal[0] + velocity[1] * self.normal[1] < 0.0
+    }
+}
+
+/// Sweeps a circle from `from` to `to` against the box spanning `min` to `max`.
+///
+/// A circle that already overlaps the box at `from` touches it at time 0.0, with the normal of
+/// the closest side.
+pub fn sweep_circle_aabb(from: [f32; 2], to: [f32; 2], radius: f32, min: [f32; 2], max: [f32; 2]) -> Option<Contact> {
+    let delta = [to[0] - from[0], to[1] - from[1]];
+    // The circle touches the box exactly when its center enters the box grown by the radius,
+    // so the sweep becomes a ray against the grown box, with rounded corners handled below.
+    let grown_min = [min[0] - radius, min[1] - radius];
+    let grown_max = [max[0] + radius, max[1] + radius];
+
+    let mut t_near = f32::NEG_INFINITY;
+    let mut t_far = f32::INFINITY;
+    let mut normal = [0.0, 0.0];
+    for axis in 0..2 {
+        if delta[axis] == 0.0 {
+            if from[axis] < grown_min[axis] || from[axis] > grown_max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t_min = (grown_min[axis] - from[axis]) / delta[axis];
+        let t_max = (grown_max[axis] - from[axis]) / delta[axis];
+        let (near, far, facing) = if t_min < t_max { (t_min, t_max, -1.0) } else { (t_max, t_min, 1.0) };
+        if near > t_near {
+            t_near = near;
+            normal = [0.0, 0.0];
+            normal[axis] = facing;
+        }
+        t_far = t_far.min(far);
+    }
+    if t_near > t_far || t_far < 0.0 || t_near > 1.0 {
+        return None;
+    }
+    if t_near < 0.0 {
+        return Some(penetration_contact(from, grown_min, grown_max));
+    }
+
+    let point = [from[0] + delta[0] * t_near, from[1] + delta[1] * t_near];
+    let corner_x = if point[0] < min[0] { Some(min[0]) } else if point[0] > max[0] { Some(max[0]) } else { None };
+    let corner_y = if point[1] < min[1] { Some(min[1]) } else if point[1] > max[1] { Some(max[1]) } else { None };
+    match (corner_x, corner_y) {
+        (Some(x), Some(y)) => sweep_circle_point(from, delta, radius, [x, y]),
+        _ => Some(Contact { time: t_near, normal, point }),
+    }
+}
+
+/// Sweeps a circle from `from` to `to` against the floor at `bottom` and the ceiling at `top`.
+pub fn sweep_circle_walls(from: [f32; 2], to: [f32; 2], radius: f32, bottom: f32, top: f32) -> Option<Contact> {
+    let (limit, normal) = if to[1] < bottom + radius && to[1] < from[1] {
+        (bottom + radius, [0.0, 1.0])
+    } else if to[1] > top - radius && to[1] > from[1] {
+        (top - radius, [0.0, -1.0])
+    } else {
+        return None;
+    };
+    let time = ((limit - from[1]) / (to[1] - from[1])).clamp(0.0, 1.0);
+    let point = [from[0] + (to[0] - from[0]) * time, limit];
+    Some(Contact { time, normal, point })
+}
+
+/// Sweeps a circle from `from` to `to` against a circle of `other_radius` at `center`.
+///
+/// A circle that already overlaps the other one at `from` touches it at time 0.0, pushed out
+/// along the line between their centers.
+pub fn sweep_circle_circle(from: [f32; 2], to: [f32; 2], radius: f32, center: [f32; 2], other_radius: f32) -> Option<Contact> {
+    let reach = radius + other_radius;
+    let offset = [from[0] - center[0], from[1] - center[1]];
+    let distance = (offset[0] * offset[0] + offset[1] * offset[1]).sqrt();
+    if distance < reach {
+        let normal = if distance > 0.0 { [offset[0] / distance, offset[1] / distance] } else { [0.0, 1.0] };
+        let point = [center[0] + normal[0] * reach, center[1] + normal[1] * reach];
+        return Some(Contact { time: 0.0, normal, point });
+    }
+    sweep_circle_point(from, [to[0] - from[0], to[1] - from[1]], reach, center)
+}
+
+/// Sweeps a circle against a single point, which is how it meets the corner of a box.
+fn sweep_circle_point(from: [f32; 2], delta: [f32; 2], radius: f32, corner: [f32; 2]) -> Option<Contact> {
+    let offset = [from[0] - corner[0], from[1] - corner[1]];
+    let a = delta[0] * delta[0] + delta[1] * delta[1];
+    let b = 2.0 * (offset[0] * delta[0] + offset[1] * delta[1]);
+    let c = offset[0] * offset[0] + offset[1] * offset[1] - radius * radius;
+    let discriminant = b * b - 4.0 * a * c;
+    if a == 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let time = (-b - discriminant.sqrt()) / (2.0 * a);
+    if !(0.0..=1.0).contains(&time) {
+        return None;
+    }
+    let point = [from[0] + delta[0] * time, from[1] + delta[1] * time];
+    let normal = [(point[0] - corner[0]) / radius, (point[1] - corner[1]) / radius];
+    Some(Contact { time, normal, point })
+}
+
+/// Pushes a center that starts inside the grown box out through its closest side.
+fn penetration_contact(from: [f32; 2], grown_min: [f32; 2], grown_max: [f32; 2]) -> Contact {
+    let exits = [
+        (from[0] - grown_min[0], [-1.0, 0.0], [grown_min[0], from[1]]),
+        (grown_max[0] - from[0], [1.0, 0.0], [grown_max[0], from[1]]),
+        (from[1] - grown_min[1], [0.0, -1.0], [from[0], grown_min[1]]),
+        (grown_max[1] - from[1], [0.0, 1.0], [from[0], grown_max[1]]),
+    ];
+    let (_, normal, point) = exits.iter()
+        .fold(exits[0], |closest, exit| if exit.0 < closest.0 { *exit } else { closest });
+    Contact { time: 0.0, normal, point }
+}
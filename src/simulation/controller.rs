@@ -0,0 +1,38 @@
+use crate::simulation::ai::{AiProfile, Difficulty};
+use crate::simulation::paddle::Side;
+
+/// Who moves a paddle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Controller {
+    /// A player, moving the paddle with the input axis of this name.
+    Human(String),
+    Ai(AiProfile),
+}
+
+/// Who plays, as picked in the players menu.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Players {
+    /// The left paddle against the AI.
+    #[default]
+    OnePlayer,
+    /// Both paddles on the same keyboard.
+    TwoPlayers,
+}
+
+impl Players {
+    pub fn controllers(self, difficulty: Difficulty) -> [Controller; 2] {
+        let right = match self {
+            Players::OnePlayer => Controller::Ai(difficulty.profile()),
+            Players::TwoPlayers => Controller::Human(axis_name(Side::Right).to_string()),
+        };
+        [Controller::Human(axis_name(Side::Left).to_string()), right]
+    }
+}
+
+/// The axis a human player on `side` moves their paddle with by default.
+pub fn axis_name(side: Side) -> &'static str {
+    match side {
+        Side::Left => "left_paddle",
+        Side::Right => "right_paddle",
+    }
+}
@@ -0,0 +1,140 @@
+use std::f32::consts::TAU;
+
+use crate::simulation::arena::Arena;
+use crate::simulation::obstacle::Obstacle;
+
+/// Least vertical speed balls keep off the top and bottom walls, any less and they would stop
+/// bouncing between them.
+const MIN_BOUNCINESS: f32 = 0.1;
+/// Most vertical speed balls gain off the top and bottom walls.
+const MAX_BOUNCINESS: f32 = 1.5;
+
+/// How an obstacle sways back and forth around where the level places it.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Motion {
+    /// The furthest it moves away from its place, it reaches it and its opposite once per period.
+    pub offset: [f32; 2],
+    /// Seconds to sway all the way there and back.
+    pub period: f32,
+}
+
+impl Motion {
+    /// How far from its place the obstacle is `elapsed` seconds into the match.
+    pub fn offset_at(&self, elapsed: f32) -> [f32; 2] {
+        if self.period <= 0.0 {
+            return [0.0, 0.0];
+        }
+        let sway = (elapsed / self.period * TAU).sin();
+        [self.offset[0] * sway, self.offset[1] * sway]
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelObstacle {
+    pub shape: Obstacle,
+    /// Obstacles without one stay in place.
+    #[serde(default)]
+    pub motion: Option<Motion>,
+}
+
+/// The layout of an arena, levels are read from `assets/levels`.
+///
+/// Positions and sizes are in arena units, from the bottom left corner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Level {
+    pub name: String,
+    /// Height of the goals as a part of the arena height, around the middle. The rest of either
+    /// end of the arena is a wall.
+    pub goal_size: f32,
+    /// How much of their vertical speed balls keep when bouncing off the top and bottom walls,
+    /// they speed up above 1 until they reach the top speed.
+    pub bounciness: f32,
+    pub obstacles: Vec<LevelObstacle>,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Level {
+            name: "Classic".to_string(),
+            goal_size: 1.0,
+            bounciness: 1.0,
+            obstacles: vec![],
+        }
+    }
+}
+
+impl Level {
+    /// Replaces values out of their range with the closest valid ones.
+    pub fn validated(mut self) -> Level {
+        let bounciness = &mut self.bounciness;
+        if !(MIN_BOUNCINESS..=MAX_BOUNCINESS).contains(bounciness) {
+            let valid = if bounciness.is_nan() { Level::default().bounciness } else { bounciness.clamp(MIN_BOUNCINESS, MAX_BOUNCINESS) };
+            log::warn!("bounciness {} of level {} is not between {} and {}, using {}", bounciness, self.name, MIN_BOUNCINESS, MAX_BOUNCINESS, valid);
+            *bounciness = valid;
+        }
+        self
+    }
+
+    /// The bottom and top of the goals.
+    pub fn goal_mouth(&self, arena: &Arena) -> (f32, f32) {
+        let size = arena.height() * self.goal_size.clamp(0.0, 1.0);
+        ((arena.height() - size) / 2.0, (arena.height() + size) / 2.0)
+    }
+
+    /// Every obstacle of the level at the start of a match, followed by the walls either side of
+    /// the goals when they don't span the whole height.
+    ///
+    /// The walls sit behind the paddles, so a ball only reaches them past a paddle.
+    pub fn obstacles(&self, arena: &Arena) -> Vec<Obstacle> {
+        let mut obstacles: Vec<Obstacle> = self.obstacles.iter().map(|obstacle| obstacle.shape).collect();
+        let (bottom, top) = self.goal_mouth(arena);
+        if bottom > 0.0 {
+            let depth = arena.paddle_width() * 0.5;
+            for (left, right) in [(0.0, depth), (arena.width() - depth, arena.width())] {
+                obstacles.push(Obstacle::Rect { min: [left, 0.0], max: [right, bottom] });
+                obstacles.push(Obstacle::Rect { min: [left, top], max: [right, arena.height()] });
+            }
+        }
+        obstacles
+    }
+
+    /// Moves the obstacles made by `obstacles` to where they are `elapsed` seconds into the match.
+    pub fn move_obstacles(&self, elapsed: f32, obstacles: &mut [Obstacle]) {
+        for (placed, obstacle) in obstacles.iter_mut().zip(self.obstacles.iter()) {
+            if let Some(motion) = obstacle.motion {
+                *placed = obstacle.shape.moved(motion.offset_at(elapsed));
+            }
+        }
+    }
+
+    /// How far the obstacle at `index` moved in the `step` seconds up to `elapsed`.
+    pub fn shift(&self, index: usize, elapsed: f32, step: f32) -> [f32; 2] {
+        match self.obstacles.get(index).and_then(|obstacle| obstacle.motion) {
+            Some(motion) => {
+                let (to, from) = (motion.offset_at(elapsed), motion.offset_at(elapsed - step));
+                [to[0] - from[0], to[1] - from[1]]
+            }
+            None => [0.0, 0.0],
+        }
+    }
+
+    pub fn has_moving_obstacles(&self) -> bool {
+        self.obstacles.iter().any(|obstacle| obstacle.motion.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_bounciness_is_kept_in_range() {
+        let bounciness = |bounciness| Level { bounciness, ..Level::default() }.validated().bounciness;
+        assert_eq!(bounciness(0.85), 0.85);
+        assert_eq!(bounciness(0.0), MIN_BOUNCINESS);
+        assert_eq!(bounciness(-2.0), MIN_BOUNCINESS);
+        assert_eq!(bounciness(10.0), MAX_BOUNCINESS);
+        assert_eq!(bounciness(f32::NAN), 1.0);
+    }
+}
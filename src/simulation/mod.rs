@@ -0,0 +1,860 @@
+pub mod ai;
+pub mod arena;
+pub mod ball;
+pub mod collision;
+pub mod obstacle;
+pub mod controller;
+pub mod level;
+pub mod paddle;
+pub mod power_up;
+pub mod rng;
+pub mod rules;
+pub mod trajectory;
+
+use rand::Rng;
+
+use ai::Ai;
+use arena::{Arena, ARENA};
+use controller::{Controller, Players};
+use ball::{BallId, BallMode, BallState, Bounce};
+use level::Level;
+use obstacle::Obstacle;
+use paddle::{PaddleState, Side, clamp_to_arena};
+use power_up::{ActiveEffect, PowerUpId, PowerUpKind, PowerUpRules, PowerUpState, GROW_FACTOR, SHRINK_FACTOR, SLOW_MOTION_FACTOR, SPEED_BOOST_FACTOR};
+use rng::GameRng;
+use rules::{MatchResult, MatchRules, Score};
+use trajectory::{PredictedContact, PredictionLimits, Surface, Surroundings, predict};
+
+/// Player input for a single tick, axis values range from -1.0 to 1.0.
+///
+/// Only the axes of paddles with a `Controller::Human` are used.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickInput {
+    pub left_paddle: f32,
+    pub right_paddle: f32,
+}
+
+impl TickInput {
+    pub fn paddle(&self, side: Side) -> f32 {
+        match side {
+            Side::Left => self.left_paddle,
+            Side::Right => self.right_paddle,
+        }
+    }
+}
+
+/// Seconds from the start of a match until the first ball is served.
+pub const SERVE_DELAY: f32 = 2.0;
+/// Seconds between extra balls joining the rally in `BallMode::Multi`.
+pub const EXTRA_BALL_INTERVAL: f32 = 8.0;
+/// Most balls in play at once in `BallMode::Multi`.
+pub const MAX_BALLS: usize = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SimEvent {
+    /// The first ball of the match was served.
+    Serve,
+    /// The ball was returned by the paddle on `side`, leaving it with a horizontal `speed`.
+    PaddleHit { side: Side, speed: f32 },
+    /// The ball bounced off the top or bottom of the arena, or an obstacle.
+    WallBounce,
+    /// The ball entered the goal behind the paddle on `side`, scoring a point for the opponent.
+    Goal { side: Side },
+    /// A ball passed through a power-up, collected by the paddle on `side`.
+    PowerUp { kind: PowerUpKind, side: Side },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationConfig {
+    pub match_rules: MatchRules,
+    /// Who moves the left and the right paddle.
+    pub controllers: [Controller; 2],
+    /// Seeds every random decision the simulation makes.
+    pub seed: u64,
+    #[serde(default)]
+    pub ball_mode: BallMode,
+    #[serde(default)]
+    pub power_ups: PowerUpRules,
+    #[serde(default)]
+    pub bounce: Bounce,
+    #[serde(default)]
+    pub level: Level,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            match_rules: MatchRules::default(),
+            controllers: Players::default().controllers(Default::default()),
+            seed: 0,
+            ball_mode: BallMode::default(),
+            power_ups: PowerUpRules::default(),
+            bounce: Bounce::default(),
+            level: Level::default(),
+        }
+    }
+}
+
+/// The game rules, without any dependency on amethyst's `Time`, `Transform` or input.
+///
+/// The game systems are thin adapters that each run one phase, `step` runs all of them in the
+/// same order so the rules can be driven without a window.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    arena: Arena,
+    balls: Vec<BallState>,
+    paddles: [PaddleState; 2],
+    level: Level,
+    /// Where the obstacles of the level are now.
+    obstacles: Vec<Obstacle>,
+    next_ball_id: BallId,
+    rules: MatchRules,
+    score: Score,
+    elapsed: f32,
+    /// Seconds the clock advanced by last, balls and obstacles moved for that long.
+    last_step: f32,
+    /// Seconds left until the first ball is served.
+    serve_timer: Option<f32>,
+    /// When the current survival run started, at the serve and again whenever the left paddle concedes.
+    run_started: f32,
+    /// Seconds the previous survival run lasted, until the left paddle conceded.
+    last_run: Option<f32>,
+    ball_mode: BallMode,
+    bounce: Bounce,
+    /// Seconds left until the next extra ball joins, counting only while fewer than `MAX_BALLS` are in play.
+    extra_ball_timer: f32,
+    power_up_rules: PowerUpRules,
+    power_ups: Vec<PowerUpState>,
+    next_power_up_id: PowerUpId,
+    /// Seconds left until the next power-up spawns.
+    power_up_timer: f32,
+    effects: Vec<ActiveEffect>,
+    controllers: [Controller; 2],
+    ais: [Option<Ai>; 2],
+    rng: GameRng,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation::new(SimulationConfig::default())
+    }
+}
+
+impl Simulation {
+    pub fn new(config: SimulationConfig) -> Self {
+        let arena = ARENA;
+        let ais = [Side::Left, Side::Right].map(|side| match config.controllers[side.index()] {
+            Controller::Ai(profile) => Some(Ai::new(profile)),
+            Controller::Human(_) => None,
+        });
+        Simulation {
+            arena,
+            balls: vec![],
+            paddles: [
+                PaddleState::new(Side::Left, &arena),
+                PaddleState::new(Side::Right, &arena),
+            ],
+            obstacles: config.level.obstacles(&arena),
+            level: config.level,
+            next_ball_id: 0,
+            rules: config.match_rules,
+            score: Score::default(),
+            elapsed: 0.0,
+            last_step: 0.0,
+            serve_timer: Some(SERVE_DELAY),
+            run_started: SERVE_DELAY,
+            last_run: None,
+            ball_mode: config.ball_mode,
+            bounce: config.bounce,
+            extra_ball_timer: EXTRA_BALL_INTERVAL,
+            power_up_timer: config.power_ups.spawn_interval,
+            power_up_rules: config.power_ups,
+            power_ups: vec![],
+            next_power_up_id: 0,
+            effects: vec![],
+            controllers: config.controllers,
+            ais,
+            rng: GameRng::new(config.seed),
+        }
+    }
+
+    pub fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    pub fn balls(&self) -> &[BallState] {
+        &self.balls
+    }
+
+    pub fn ball(&self, id: BallId) -> Option<&BallState> {
+        self.balls.iter().find(|ball| ball.id == id)
+    }
+
+    pub fn paddle(&self, side: Side) -> &PaddleState {
+        &self.paddles[side.index()]
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
+
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+
+    /// Everything balls bounce off right now.
+    pub fn surroundings(&self) -> Surroundings<'_> {
+        Surroundings {
+            arena: self.arena,
+            paddles: &self.paddles,
+            obstacles: &self.obstacles,
+            level: &self.level,
+            bounce: self.bounce,
+            elapsed: self.elapsed,
+            step: self.last_step,
+            time_scale: self.ball_time_scale(),
+        }
+    }
+
+    /// Everything `ball` will touch until it reaches a goal, with paddles and obstacles moving on as they do now.
+    ///
+    /// Times are in seconds of the game, so they account for effects that speed balls up or slow them down.
+    pub fn predict(&self, ball: &BallState, limits: PredictionLimits) -> Vec<PredictedContact> {
+        predict(ball, &self.surroundings(), limits)
+    }
+
+    pub fn controller(&self, side: Side) -> &Controller {
+        &self.controllers[side.index()]
+    }
+
+    pub fn ball_mode(&self) -> BallMode {
+        self.ball_mode
+    }
+
+    pub fn power_up_rules(&self) -> &PowerUpRules {
+        &self.power_up_rules
+    }
+
+    pub fn power_ups(&self) -> &[PowerUpState] {
+        &self.power_ups
+    }
+
+    pub fn effects(&self) -> &[ActiveEffect] {
+        &self.effects
+    }
+
+    /// Whether fog hides `ball`, which it does in the half of the opponent of whoever collected it.
+    pub fn is_fogged(&self, ball: &BallState) -> bool {
+        let middle = self.arena.width() / 2.0;
+        self.effects.iter()
+            .filter(|effect| effect.kind == PowerUpKind::Fog)
+            .any(|effect| match effect.side.opponent() {
+                Side::Left => ball.position[0] < middle,
+                Side::Right => ball.position[0] > middle,
+            })
+    }
+
+    pub fn rules(&self) -> &MatchRules {
+        &self.rules
+    }
+
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    /// The seed the match was started with.
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Seconds the match has been running.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Seconds the left paddle has gone without conceding, since the serve or its last goal conceded.
+    pub fn survived(&self) -> f32 {
+        (self.elapsed - self.run_started).max(0.0)
+    }
+
+    /// Seconds the previous survival run lasted, `None` until the left paddle first concedes.
+    pub fn last_run(&self) -> Option<f32> {
+        self.last_run
+    }
+
+    /// The result of the match, or `None` while it is still being played.
+    pub fn match_result(&self) -> Option<MatchResult> {
+        self.rules.result(&self.score, self.elapsed)
+    }
+
+    /// Spawns a ball in the middle of the arena.
+    pub fn spawn_ball(&mut self, velocity: [f32; 2]) -> BallId {
+        let id = self.next_ball_id;
+        self.next_ball_id += 1;
+        let center = [self.arena.width() / 2.0, self.arena.height() / 2.0];
+        self.balls.push(BallState {
+            id,
+            position: center,
+            previous_position: center,
+            velocity,
+            radius: self.arena.ball_radius(),
+            calculated_impact_y: None,
+            spin: 0.0,
+            teleported: true,
+        });
+        id
+    }
+
+    /// Spawns a ball in the middle of the arena, heading in a random diagonal direction.
+    pub fn serve_ball(&mut self) -> BallId {
+        let mult_x = if self.rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        let mult_y = if self.rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        self.spawn_ball([self.arena.ball_velocity_x() * mult_x, self.arena.ball_velocity_y() * mult_y])
+    }
+
+    /// Advances the game by `dt` seconds.
+    pub fn step(&mut self, dt: f32, input: &TickInput) -> Vec<SimEvent> {
+        let mut events = self.advance_clock(dt);
+        self.move_balls(dt);
+        events.extend(self.collect_power_ups());
+        self.update_trajectories();
+        self.move_paddles(dt, input);
+        events.extend(self.bounce());
+        events.extend(self.check_goals());
+        events
+    }
+
+    /// Counts the match time, and serves the first ball once the serve delay is over.
+    ///
+    /// In `BallMode::Multi` an extra ball is served every `EXTRA_BALL_INTERVAL` afterwards, up to `MAX_BALLS`.
+    /// Obstacles move, power-ups spawn and their effects wear off with the same clock.
+    pub fn advance_clock(&mut self, dt: f32) -> Vec<SimEvent> {
+        self.elapsed += dt;
+        self.last_step = dt;
+        self.level.move_obstacles(self.elapsed, &mut self.obstacles);
+        self.tick_power_ups(dt);
+        if let Some(timer) = self.serve_timer.as_mut() {
+            *timer -= dt;
+            if *timer <= 0.0 {
+                self.serve_timer = None;
+                self.serve_ball();
+                return vec![SimEvent::Serve];
+            }
+        } else if self.ball_mode == BallMode::Multi && self.balls.len() < MAX_BALLS {
+            self.extra_ball_timer -= dt;
+            if self.extra_ball_timer <= 0.0 {
+                self.extra_ball_timer = EXTRA_BALL_INTERVAL;
+                self.serve_ball();
+            }
+        }
+        vec![]
+    }
+
+    /// Move every ball according to its speed, its spin, and the time passed.
+    pub fn move_balls(&mut self, dt: f32) {
+        let dt = dt * self.ball_time_scale();
+        for ball in self.balls.iter_mut() {
+            ball.teleported = false;
+            ball.advance(dt);
+        }
+    }
+
+    /// Calculates where balls will reach the end of the arena they are heading to, past walls and obstacles.
+    pub fn update_trajectories(&mut self) {
+        let surroundings = Surroundings {
+            arena: self.arena,
+            paddles: &[],
+            obstacles: &self.obstacles,
+            level: &self.level,
+            bounce: self.bounce,
+            elapsed: self.elapsed,
+            step: self.last_step,
+            time_scale: self.ball_time_scale(),
+        };
+        // Predictions step like the game did last, so they see the same contacts the balls will.
+        let limits = if self.last_step > 0.0 {
+            PredictionLimits { step: self.last_step, ..Default::default() }
+        } else {
+            PredictionLimits::default()
+        };
+        for ball in self.balls.iter_mut() {
+            if ball.calculated_impact_y.is_none() && ball.velocity[0] != 0.0 {
+                // The ball is defended where it reaches either end, the goal or the walls beside it.
+                let goal = predict(ball, &surroundings, limits).into_iter()
+                    .find(|contact| matches!(contact.surface, Surface::Goal(_) | Surface::EndWall(_)));
+                // A ball too slow to reach a goal within the horizon is defended where it is.
+                let impact = goal.map_or(ball.position[1], |contact| contact.point[1]);
+                ball.calculated_impact_y.replace(impact);
+            }
+        }
+    }
+
+    /// Moves human paddles by their input and lets the AI move the others.
+    pub fn move_paddles(&mut self, dt: f32, input: &TickInput) {
+        let arena = self.arena;
+        for paddle in self.paddles.iter_mut() {
+            let moved_from = paddle.y;
+            match self.ais[paddle.side.index()].as_mut() {
+                Some(ai) => ai.steer(paddle, &self.balls, dt, &arena, &mut self.rng),
+                None => {
+                    let axis = input.paddle(paddle.side).clamp(-1.0, 1.0);
+                    let scaled_amount = arena.paddle_speed() * axis * dt;
+                    paddle.y = clamp_to_arena(paddle.y + scaled_amount, paddle.height, &arena);
+                }
+            }
+            paddle.velocity = if dt > 0.0 { (paddle.y - moved_from) / dt } else { 0.0 };
+        }
+    }
+
+    /// Sweeps every ball along the path it moved this tick, and bounces it off the first wall,
+    /// paddle or obstacle it touched.
+    ///
+    /// Only surfaces a ball is heading into count, to prevent multiple collisions from occurring.
+    pub fn bounce(&mut self) -> Vec<SimEvent> {
+        let mut events = vec![];
+        let surroundings = Surroundings {
+            arena: self.arena,
+            paddles: &self.paddles,
+            obstacles: &self.obstacles,
+            level: &self.level,
+            bounce: self.bounce,
+            elapsed: self.elapsed,
+            step: self.last_step,
+            time_scale: self.ball_time_scale(),
+        };
+        for ball in self.balls.iter_mut() {
+            if let Some((surface, contact)) = surroundings.first_contact(ball) {
+                surroundings.bounce_off(ball, surface, &contact);
+                events.push(match surface {
+                    Surface::Paddle(side) => SimEvent::PaddleHit { side, speed: ball.velocity[0].abs() },
+                    _ => SimEvent::WallBounce,
+                });
+            }
+        }
+        events
+    }
+
+    /// Scores balls that reached either edge of the arena and resets them to the middle.
+    ///
+    /// The scoring ball is removed instead while another ball is still in play, in `BallMode::Multi`
+    /// or after a split.
+    pub fn check_goals(&mut self) -> Vec<SimEvent> {
+        let mut events = vec![];
+        let arena = self.arena;
+        let goals: Vec<(BallId, Side)> = self.balls.iter()
+            .filter_map(|ball| ball.goal(&arena).map(|side| (ball.id, side)))
+            .collect();
+
+        for (id, side) in goals {
+            if self.balls.len() > 1 {
+                self.balls.retain(|ball| ball.id != id);
+            } else if let Some(ball) = self.balls.iter_mut().find(|ball| ball.id == id) {
+                if ball.velocity[0].is_sign_positive() {
+                    ball.velocity[0] = -arena.ball_velocity_x();
+                } else {
+                    ball.velocity[0] = arena.ball_velocity_x();
+                }
+                ball.velocity[1] = arena.ball_velocity_y();
+                ball.position = [arena.width() / 2.0, arena.height() / 2.0];
+                ball.previous_position = ball.position;
+                ball.calculated_impact_y = None;
+                ball.spin = 0.0;
+                ball.teleported = true;
+            }
+            // A second goal in the same tick ends no run of its own.
+            if side == Side::Left && self.elapsed > self.run_started {
+                self.last_run = Some(self.survived());
+                self.run_started = self.elapsed;
+            }
+            self.score.add_point(side.opponent());
+            events.push(SimEvent::Goal { side });
+        }
+        events
+    }
+
+    /// Collects the power-ups balls passed through this tick and applies their effects.
+    pub fn collect_power_ups(&mut self) -> Vec<SimEvent> {
+        let mut events = vec![];
+        let collected: Vec<(PowerUpId, BallState)> = self.power_ups.iter()
+            .filter_map(|power_up| self.balls.iter()
+                .find(|ball| !ball.teleported && power_up.is_touched_by(ball.previous_position, ball.position, ball.radius))
+                .map(|ball| (power_up.id, *ball)))
+            .collect();
+
+        for (id, ball) in collected {
+            let kind = match self.power_ups.iter().find(|power_up| power_up.id == id) {
+                Some(power_up) => power_up.kind,
+                None => continue,
+            };
+            self.power_ups.retain(|power_up| power_up.id != id);
+            let side = if ball.velocity[0] > 0.0 { Side::Left } else { Side::Right };
+            if !kind.is_timed() {
+                if self.balls.len() < MAX_BALLS {
+                    self.split_ball(&ball);
+                }
+            } else {
+                let duration = self.power_up_rules.duration;
+                match self.effects.iter_mut().find(|effect| effect.kind == kind && effect.side == side) {
+                    Some(effect) => effect.remaining = duration,
+                    None => self.effects.push(ActiveEffect { kind, side, remaining: duration }),
+                }
+            }
+            events.push(SimEvent::PowerUp { kind, side });
+        }
+        self.resize_paddles();
+        events
+    }
+
+    /// Wears off effects and expires power-ups, and spawns new ones while a ball is in play.
+    fn tick_power_ups(&mut self, dt: f32) {
+        for effect in self.effects.iter_mut() {
+            effect.remaining -= dt;
+        }
+        self.effects.retain(|effect| effect.remaining > 0.0);
+        for power_up in self.power_ups.iter_mut() {
+            power_up.expires_in -= dt;
+        }
+        self.power_ups.retain(|power_up| power_up.expires_in > 0.0);
+
+        if self.power_up_rules.enabled && !self.balls.is_empty() && self.power_ups.len() < self.power_up_rules.max_in_arena {
+            self.power_up_timer -= dt;
+            if self.power_up_timer <= 0.0 {
+                self.power_up_timer = self.power_up_rules.spawn_interval;
+                self.spawn_power_up();
+            }
+        }
+        self.resize_paddles();
+    }
+
+    /// Spawns a power-up somewhere around the middle, out of reach of the paddles.
+    fn spawn_power_up(&mut self) {
+        let kind = match self.power_up_rules.pick(&mut self.rng) {
+            Some(kind) => kind,
+            None => return,
+        };
+        let position = [
+            self.arena.width() * self.rng.gen_range(0.3..0.7),
+            self.arena.height() * self.rng.gen_range(0.15..0.85),
+        ];
+        let id = self.next_power_up_id;
+        self.next_power_up_id += 1;
+        self.power_ups.push(PowerUpState {
+            id,
+            kind,
+            position,
+            radius: self.arena.power_up_radius(),
+            expires_in: self.power_up_rules.lifetime,
+        });
+    }
+
+    /// Adds a copy of `ball` heading the other way vertically.
+    fn split_ball(&mut self, ball: &BallState) {
+        let id = self.next_ball_id;
+        self.next_ball_id += 1;
+        self.balls.push(BallState {
+            id,
+            previous_position: ball.position,
+            velocity: [ball.velocity[0], -ball.velocity[1]],
+            spin: -ball.spin,
+            calculated_impact_y: None,
+            teleported: true,
+            ..*ball
+        });
+    }
+
+    /// Sizes the paddles by the `Grow` and `Shrink` effects, keeping them inside the arena.
+    fn resize_paddles(&mut self) {
+        let arena = self.arena;
+        let effects = &self.effects;
+        for paddle in self.paddles.iter_mut() {
+            let factor = effects.iter().fold(1.0, |factor, effect| match effect.kind {
+                PowerUpKind::Grow if effect.side == paddle.side => factor * GROW_FACTOR,
+                PowerUpKind::Shrink if effect.side == paddle.side.opponent() => factor * SHRINK_FACTOR,
+                _ => factor,
+            });
+            paddle.height = arena.paddle_height() * factor;
+            paddle.y = clamp_to_arena(paddle.y, paddle.height, &arena);
+        }
+    }
+
+    /// How much faster than their velocity balls move, by the `SpeedBoost` and `SlowMotion` effects.
+    fn ball_time_scale(&self) -> f32 {
+        self.effects.iter().fold(1.0, |scale, effect| match effect.kind {
+            PowerUpKind::SpeedBoost => scale * SPEED_BOOST_FACTOR,
+            PowerUpKind::SlowMotion => scale * SLOW_MOTION_FACTOR,
+            _ => scale,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::level::{LevelObstacle, Motion};
+
+    const STEP: f32 = 1.0 / 120.0;
+
+    /// A match between two players who never move, so only the ball does anything.
+    fn still_paddles() -> Simulation {
+        Simulation::new(SimulationConfig {
+            controllers: Players::TwoPlayers.controllers(Default::default()),
+            ..SimulationConfig::default()
+        })
+    }
+
+    /// Steps until `found` returns something for an event, or panics after `seconds`.
+    fn step_until<T>(simulation: &mut Simulation, seconds: f32, mut found: impl FnMut(&SimEvent) -> Option<T>) -> T {
+        let mut elapsed = 0.0;
+        while elapsed < seconds {
+            elapsed += STEP;
+            if let Some(found) = simulation.step(STEP, &TickInput::default()).iter().find_map(&mut found) {
+                return found;
+            }
+        }
+        panic!("nothing found within {} seconds", seconds);
+    }
+
+    /// Places the only ball at `position`, moving at `velocity` and serves no more balls.
+    fn place_ball(simulation: &mut Simulation, position: [f32; 2], velocity: [f32; 2]) {
+        simulation.serve_timer = None;
+        simulation.balls.clear();
+        simulation.spawn_ball(velocity);
+        let ball = &mut simulation.balls[0];
+        ball.position = position;
+        ball.previous_position = position;
+    }
+
+    #[test]
+    fn serve_reaches_goal() {
+        let mut simulation = still_paddles();
+        step_until(&mut simulation, SERVE_DELAY + STEP, |event| (*event == SimEvent::Serve).then_some(()));
+        assert_eq!(simulation.balls().len(), 1);
+        // Flattened below the paddles, so nothing stops it.
+        let arena = *simulation.arena();
+        let ball = &mut simulation.balls[0];
+        ball.velocity[1] = 0.0;
+        ball.position[1] = arena.height() * 0.1;
+        ball.previous_position = ball.position;
+        let heading_to = if ball.velocity[0] < 0.0 { Side::Left } else { Side::Right };
+
+        let side = step_until(&mut simulation, 5.0, |event| match event {
+            SimEvent::Goal { side } => Some(*side),
+            _ => None,
+        });
+        assert_eq!(side, heading_to);
+    }
+
+    #[test]
+    fn paddle_bounce_reverses_horizontal_velocity() {
+        let mut simulation = still_paddles();
+        let arena = *simulation.arena();
+        place_ball(&mut simulation, [arena.width() / 2.0, arena.height() / 2.0], [-arena.ball_velocity_x(), 0.0]);
+
+        let side = step_until(&mut simulation, 5.0, |event| match event {
+            SimEvent::PaddleHit { side, .. } => Some(*side),
+            SimEvent::Goal { .. } => panic!("the ball went past the paddle"),
+            _ => None,
+        });
+        assert_eq!(side, Side::Left);
+        assert!(simulation.balls()[0].velocity[0] > 0.0);
+    }
+
+    #[test]
+    fn goal_scores_for_the_opponent_and_resets_the_ball() {
+        let mut simulation = still_paddles();
+        let arena = *simulation.arena();
+        place_ball(&mut simulation, [arena.width() / 2.0, arena.height() * 0.1], [-arena.ball_velocity_x(), 0.0]);
+
+        let side = step_until(&mut simulation, 5.0, |event| match event {
+            SimEvent::Goal { side } => Some(*side),
+            _ => None,
+        });
+        assert_eq!(side, Side::Left);
+        assert_eq!(*simulation.score(), Score { left: 0, right: 1 });
+        let ball = &simulation.balls()[0];
+        assert_eq!(ball.position, [arena.width() / 2.0, arena.height() / 2.0]);
+        assert_eq!(ball.velocity, [arena.ball_velocity_x(), arena.ball_velocity_y()]);
+        assert!(ball.teleported);
+        assert!(ball.calculated_impact_y.is_none());
+    }
+
+    #[test]
+    fn goal_in_multi_mode_takes_out_only_the_scoring_ball() {
+        let mut simulation = Simulation::new(SimulationConfig {
+            ball_mode: BallMode::Multi,
+            controllers: Players::TwoPlayers.controllers(Default::default()),
+            ..SimulationConfig::default()
+        });
+        let arena = *simulation.arena();
+        place_ball(&mut simulation, [arena.width() / 2.0, arena.height() * 0.1], [-arena.ball_velocity_x(), 0.0]);
+        let scoring = simulation.balls[0].id;
+        let staying = simulation.spawn_ball([arena.ball_velocity_x(), 0.0]);
+        simulation.balls[1].position = [arena.width() / 4.0, arena.height() * 0.9];
+        simulation.balls[1].previous_position = simulation.balls[1].position;
+
+        step_until(&mut simulation, 5.0, |event| (*event == SimEvent::Goal { side: Side::Left }).then_some(()));
+        let left: Vec<BallId> = simulation.balls().iter().map(|ball| ball.id).collect();
+        assert_eq!(left, vec![staying]);
+        assert_ne!(staying, scoring);
+        assert_eq!(*simulation.score(), Score { left: 0, right: 1 });
+    }
+
+    #[test]
+    fn survival_runs_from_the_serve_until_the_left_paddle_concedes() {
+        let mut simulation = still_paddles();
+        let arena = *simulation.arena();
+        step_until(&mut simulation, SERVE_DELAY + STEP, |event| (*event == SimEvent::Serve).then_some(()));
+        assert!(simulation.survived() < STEP);
+        place_ball(&mut simulation, [arena.width() / 2.0, arena.height() * 0.1], [-arena.ball_velocity_x(), 0.0]);
+
+        step_until(&mut simulation, 5.0, |event| (*event == SimEvent::Goal { side: Side::Left }).then_some(()));
+        let run = simulation.last_run().unwrap();
+        assert!((run - (simulation.elapsed() - SERVE_DELAY)).abs() < STEP);
+        assert_eq!(simulation.survived(), 0.0);
+        simulation.step(STEP, &TickInput::default());
+        assert!((simulation.survived() - STEP).abs() < 1e-4);
+        assert_eq!(simulation.last_run(), Some(run));
+    }
+
+    #[test]
+    fn moving_obstacle_is_touched_when_it_meets_the_ball() {
+        let arena = ARENA;
+        // A quarter period in, the box has moved 100 units left over the last second.
+        let level = Level {
+            obstacles: vec![LevelObstacle {
+                shape: Obstacle::Rect { min: [800.0, 400.0], max: [820.0, 500.0] },
+                motion: Some(Motion { offset: [-100.0, 0.0], period: 4.0 }),
+            }],
+            ..Level::default()
+        };
+        let mut obstacles = level.obstacles(&arena);
+        level.move_obstacles(1.0, &mut obstacles);
+        let surroundings = Surroundings {
+            arena,
+            paddles: &[],
+            obstacles: &obstacles,
+            level: &level,
+            bounce: Bounce::Classic,
+            elapsed: 1.0,
+            step: 1.0,
+            time_scale: 1.0,
+        };
+        let ball = BallState {
+            id: 0,
+            position: [700.0, 450.0],
+            previous_position: [600.0, 450.0],
+            velocity: [100.0, 0.0],
+            radius: 10.0,
+            calculated_impact_y: None,
+            spin: 0.0,
+            teleported: false,
+        };
+
+        // The gap of 190 units closes at 200 units per second, from either side.
+        let (surface, contact) = surroundings.first_contact(&ball).unwrap();
+        assert_eq!(surface, Surface::Obstacle(0));
+        assert!((contact.time - 0.95).abs() < 1e-4, "touched at {}", contact.time);
+    }
+
+    #[test]
+    fn ball_at_top_speed_does_not_tunnel_through_a_paddle() {
+        let mut simulation = still_paddles();
+        let arena = *simulation.arena();
+        // The longest fixed step the settings allow moves the ball further in a tick than the paddle and the ball are wide together.
+        let step = 1.0 / 20.0;
+        assert!(arena.max_velocity() * step > arena.paddle_width() + 2.0 * arena.ball_radius());
+        place_ball(&mut simulation, [arena.width() / 2.0, arena.height() / 2.0], [-arena.max_velocity(), 0.0]);
+
+        let mut hit = false;
+        for _ in 0..40 {
+            for event in simulation.step(step, &TickInput::default()) {
+                match event {
+                    SimEvent::PaddleHit { side: Side::Left, .. } => hit = true,
+                    SimEvent::Goal { .. } => panic!("the ball went through the paddle"),
+                    _ => {}
+                }
+            }
+            if hit {
+                break;
+            }
+        }
+        assert!(hit);
+        let ball = &simulation.balls()[0];
+        assert!(ball.velocity[0] > 0.0);
+        assert!(ball.position[0] >= arena.paddle_width() + ball.radius - 0.01);
+    }
+
+    #[test]
+    fn match_result_follows_the_score_of_the_simulation() {
+        let mut simulation = Simulation::new(SimulationConfig {
+            match_rules: MatchRules::FirstTo { points: 1, win_by_two: false },
+            controllers: Players::TwoPlayers.controllers(Default::default()),
+            ..SimulationConfig::default()
+        });
+        let arena = *simulation.arena();
+        place_ball(&mut simulation, [arena.width() / 2.0, arena.height() * 0.1], [arena.ball_velocity_x(), 0.0]);
+        assert_eq!(simulation.match_result(), None);
+        step_until(&mut simulation, 5.0, |event| matches!(event, SimEvent::Goal { .. }).then_some(()));
+        assert_eq!(simulation.match_result(), Some(MatchResult::Winner(Side::Left)));
+    }
+
+    /// Steps a match between the AI and a player whose input follows the tick.
+    fn play(config: SimulationConfig, ticks: u32) -> Simulation {
+        let mut simulation = Simulation::new(config);
+        for tick in 0..ticks {
+            let input = TickInput { left_paddle: ((tick / 90) as f32).sin(), right_paddle: 0.0 };
+            simulation.step(STEP, &input);
+        }
+        simulation
+    }
+
+    #[test]
+    fn same_seed_and_input_play_the_same_match() {
+        let config = SimulationConfig { seed: 7, ..SimulationConfig::default() };
+        let first = play(config.clone(), 120 * 30);
+        let second = play(config, 120 * 30);
+        assert_eq!(format!("{:?}", first), format!("{:?}", second));
+    }
+
+    #[test]
+    fn predicted_contacts_are_the_ones_the_ball_makes() {
+        // Coarser than the default step, predictions have to follow the step the game takes.
+        let step = 1.0 / 50.0;
+        let mut simulation = still_paddles();
+        let arena = simulation.arena;
+        place_ball(&mut simulation, [arena.width() / 2.0, arena.height() / 3.0], [arena.ball_velocity_x(), arena.ball_velocity_y() * 4.0]);
+        simulation.balls[0].spin = 80.0;
+        let predicted = simulation.predict(&simulation.balls[0], PredictionLimits { step, ..Default::default() });
+        assert!(predicted.len() >= 3, "too few contacts to compare: {:?}", predicted);
+
+        let mut made = vec![];
+        let mut tick = 0;
+        while !matches!(made.last(), Some((_, SimEvent::Goal { .. }, _))) {
+            assert!(tick < 1000, "the ball never reached a goal");
+            tick += 1;
+            for event in simulation.step(step, &TickInput::default()) {
+                if matches!(event, SimEvent::PaddleHit { .. } | SimEvent::WallBounce | SimEvent::Goal { .. }) {
+                    let ball = simulation.balls.first().copied();
+                    made.push((tick, event, ball));
+                }
+            }
+        }
+
+        assert_eq!(predicted.len(), made.len(), "predicted {:?}, made {:?}", predicted, made);
+        for (contact, (tick, event, ball)) in predicted.iter().zip(&made) {
+            assert_eq!((contact.time / step).floor() as i32 + 1, *tick, "{:?} came on tick {}", contact, tick);
+            match (contact.surface, event) {
+                (Surface::Goal(side), SimEvent::Goal { side: scored }) => assert_eq!(side, *scored),
+                (Surface::Paddle(side), SimEvent::PaddleHit { side: hit, .. }) => assert_eq!(side, *hit),
+                (Surface::Wall, SimEvent::WallBounce) => {}
+                _ => panic!("predicted {:?}, the ball made {:?}", contact, event),
+            }
+            if let (Some(ball), false) = (ball, matches!(event, SimEvent::Goal { .. })) {
+                for axis in 0..2 {
+                    assert!((ball.position[axis] - contact.point[axis]).abs() < 1e-2, "{:?} ended at {:?}", contact, ball.position);
+                    assert!((ball.velocity[axis] - contact.velocity[axis]).abs() < 1e-2, "{:?} left at {:?}", contact, ball.velocity);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::simulation::collision::{Contact, sweep_circle_aabb, sweep_circle_circle};
+
+/// Something in the arena balls bounce off, besides the walls and the paddles.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Obstacle {
+    /// A box spanning from its bottom left to its top right corner.
+    Rect { min: [f32; 2], max: [f32; 2] },
+    Circle { center: [f32; 2], radius: f32 },
+}
+
+impl Obstacle {
+    /// Where a ball of `radius` moving from `from` to `to` first touches the obstacle.
+    pub fn sweep(&self, from: [f32; 2], to: [f32; 2], radius: f32) -> Option<Contact> {
+        match *self {
+            Obstacle::Rect { min, max } => sweep_circle_aabb(from, to, radius, min, max),
+            Obstacle::Circle { center, radius: obstacle_radius } => sweep_circle_circle(from, to, radius, center, obstacle_radius),
+        }
+    }
+
+    /// The same obstacle moved by `offset`.
+    pub fn moved(&self, offset: [f32; 2]) -> Obstacle {
+        match *self {
+            Obstacle::Rect { min, max } => Obstacle::Rect {
+                min: [min[0] + offset[0], min[1] + offset[1]],
+                max: [max[0] + offset[0], max[1] + offset[1]],
+            },
+            Obstacle::Circle { center, radius } => Obstacle::Circle {
+                center: [center[0] + offset[0], center[1] + offset[1]],
+                radius,
+            },
+        }
+    }
+
+    pub fn center(&self) -> [f32; 2] {
+        match *self {
+            Obstacle::Rect { min, max } => [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0],
+            Obstacle::Circle { center, .. } => center,
+        }
+    }
+
+    /// Width and height of the box around the obstacle.
+    pub fn size(&self) -> [f32; 2] {
+        match *self {
+            Obstacle::Rect { min, max } => [max[0] - min[0], max[1] - min[1]],
+            Obstacle::Circle { radius, .. } => [radius * 2.0, radius * 2.0],
+        }
+    }
+}
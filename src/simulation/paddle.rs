@@ -0,0 +1,77 @@
+use crate::simulation::arena::Arena;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    pub fn index(self) -> usize {
+        match self {
+            Side::Left => 0,
+            Side::Right => 1,
+        }
+    }
+
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PaddleState {
+    pub side: Side,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Vertical speed of the last move, in arena units per second.
+    pub velocity: f32,
+}
+
+impl PaddleState {
+    /// Creates a paddle vertically centered against its own wall.
+    pub fn new(side: Side, arena: &Arena) -> PaddleState {
+        PaddleState {
+            side,
+            x: paddle_x(side, arena),
+            y: arena.height() / 2.0,
+            width: arena.paddle_width(),
+            height: arena.paddle_height(),
+            velocity: 0.0,
+        }
+    }
+
+    /// Where the paddle will be in `seconds` if it keeps moving at the speed of its last move.
+    pub fn extrapolate(&self, seconds: f32, arena: &Arena) -> f32 {
+        clamp_to_arena(self.y + self.velocity * seconds, self.height, arena)
+    }
+
+    /// The bottom left and top right corners of the paddle.
+    pub fn bounds(&self) -> ([f32; 2], [f32; 2]) {
+        (
+            [self.x - self.width * 0.5, self.y - self.height * 0.5],
+            [self.x + self.width * 0.5, self.y + self.height * 0.5],
+        )
+    }
+}
+
+pub(crate) fn paddle_x(side: Side, arena: &Arena) -> f32 {
+    match side {
+        Side::Left => arena.paddle_width() * 0.5,
+        Side::Right => arena.width() - arena.paddle_width() * 0.5,
+    }
+}
+
+pub(crate) fn calc_to_move(distance: f32, mv: f32) -> f32 {
+    if distance > mv { mv } else if mv > distance { distance } else { 0.0 }
+}
+
+pub(crate) fn clamp_to_arena(val: f32, height: f32, arena: &Arena) -> f32 {
+    val.min(arena.height() - height * 0.5)
+        .max(height * 0.5)
+}
@@ -0,0 +1,277 @@
+use rand::Rng;
+
+use crate::simulation::paddle::Side;
+use crate::simulation::rng::GameRng;
+
+pub type PowerUpId = u32;
+
+/// How much the paddle of whoever collected a `Grow` grows.
+pub const GROW_FACTOR: f32 = 1.5;
+/// How much the opponent's paddle shrinks after a `Shrink`.
+pub const SHRINK_FACTOR: f32 = 0.6;
+pub const SPEED_BOOST_FACTOR: f32 = 1.5;
+pub const SLOW_MOTION_FACTOR: f32 = 0.5;
+
+/// What collecting a power-up does, it goes to the paddle the ball was moving away from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerUpKind {
+    /// The paddle of whoever collected it grows.
+    Grow,
+    /// The opponent's paddle shrinks.
+    Shrink,
+    /// Every ball moves faster.
+    SpeedBoost,
+    /// The ball that collected it splits in two, right away.
+    Split,
+    /// Every ball moves slower.
+    SlowMotion,
+    /// Balls can't be seen in the opponent's half, the AI half when the player collected it.
+    Fog,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 6] = [
+        PowerUpKind::Grow,
+        PowerUpKind::Shrink,
+        PowerUpKind::SpeedBoost,
+        PowerUpKind::Split,
+        PowerUpKind::SlowMotion,
+        PowerUpKind::Fog,
+    ];
+
+    /// Whether the effect lasts for `PowerUpRules::duration`, rather than happening once.
+    pub fn is_timed(self) -> bool {
+        self != PowerUpKind::Split
+    }
+}
+
+/// A power-up waiting in the arena for a ball to pass through it.
+#[derive(Debug, Copy, Clone)]
+pub struct PowerUpState {
+    pub id: PowerUpId,
+    pub kind: PowerUpKind,
+    pub position: [f32; 2],
+    pub radius: f32,
+    /// Seconds left until it disappears uncollected.
+    pub expires_in: f32,
+}
+
+impl PowerUpState {
+    /// Whether a ball of `radius` moving from `from` to `to` touched the power-up on the way.
+    pub fn is_touched_by(&self, from: [f32; 2], to: [f32; 2], radius: f32) -> bool {
+        let delta = [to[0] - from[0], to[1] - from[1]];
+        let length = delta[0] * delta[0] + delta[1] * delta[1];
+        let t = if length > 0.0 {
+            (((self.position[0] - from[0]) * delta[0] + (self.position[1] - from[1]) * delta[1]) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let closest = [from[0] + delta[0] * t - self.position[0], from[1] + delta[1] * t - self.position[1]];
+        let reach = self.radius + radius;
+        closest[0] * closest[0] + closest[1] * closest[1] <= reach * reach
+    }
+}
+
+/// A timed effect of a collected power-up.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ActiveEffect {
+    pub kind: PowerUpKind,
+    /// Who collected it.
+    pub side: Side,
+    /// Seconds left, it only counts down while the simulation runs so pausing stops it.
+    pub remaining: f32,
+}
+
+/// When and which power-ups spawn, the game reads them from `power_ups.ron`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerUpRules {
+    /// No power-ups spawn while disabled.
+    pub enabled: bool,
+    /// Seconds between power-ups spawning while a ball is in play.
+    pub spawn_interval: f32,
+    /// Most power-ups waiting in the arena at once.
+    pub max_in_arena: usize,
+    /// Seconds a power-up waits to be collected.
+    pub lifetime: f32,
+    /// Seconds the timed effects last.
+    pub duration: f32,
+    /// How often each kind spawns compared to the others, kinds left out never spawn.
+    pub weights: Vec<(PowerUpKind, u32)>,
+}
+
+impl Default for PowerUpRules {
+    fn default() -> Self {
+        PowerUpRules {
+            enabled: false,
+            spawn_interval: 10.0,
+            max_in_arena: 2,
+            lifetime: 12.0,
+            duration: 8.0,
+            weights: PowerUpKind::ALL.iter().map(|kind| (*kind, 1)).collect(),
+        }
+    }
+}
+
+impl PowerUpRules {
+    /// Picks the kind of the next power-up by its weight, `None` when every weight is zero.
+    pub fn pick(&self, rng: &mut GameRng) -> Option<PowerUpKind> {
+        let total: u32 = self.weights.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0..total);
+        for (kind, weight) in self.weights.iter() {
+            if roll < *weight {
+                return Some(*kind);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::controller::Players;
+    use crate::simulation::{SimEvent, Simulation, SimulationConfig, TickInput};
+
+    const STEP: f32 = 1.0 / 120.0;
+    const DURATION: f32 = 8.0;
+
+    /// A match where a single ball heads right through a `kind` power-up just ahead of it, so the
+    /// left paddle collects it. No other power-ups spawn.
+    fn about_to_collect(kind: PowerUpKind) -> Simulation {
+        let mut simulation = Simulation::new(SimulationConfig {
+            controllers: Players::TwoPlayers.controllers(Default::default()),
+            power_ups: PowerUpRules { enabled: false, duration: DURATION, ..PowerUpRules::default() },
+            ..SimulationConfig::default()
+        });
+        let arena = simulation.arena;
+        let middle = [arena.width() / 2.0, arena.height() * 0.3];
+        simulation.serve_timer = None;
+        simulation.balls.clear();
+        simulation.spawn_ball([arena.ball_velocity_x(), arena.ball_velocity_y()]);
+        simulation.balls[0].position = middle;
+        simulation.balls[0].previous_position = middle;
+        simulation.power_ups.push(PowerUpState {
+            id: 0,
+            kind,
+            position: [middle[0] + 50.0, middle[1]],
+            radius: arena.power_up_radius(),
+            expires_in: 10.0,
+        });
+        simulation
+    }
+
+    /// Steps until the power-up is collected, returning who got it.
+    fn collect(simulation: &mut Simulation) -> Side {
+        for _ in 0..120 {
+            let events = simulation.step(STEP, &TickInput::default());
+            if let Some(side) = events.iter().find_map(|event| match event {
+                SimEvent::PowerUp { side, .. } => Some(*side),
+                _ => None,
+            }) {
+                return side;
+            }
+        }
+        panic!("the power-up was never collected");
+    }
+
+    #[test]
+    fn passing_a_pickup_applies_its_effect() {
+        for kind in PowerUpKind::ALL.iter().copied().filter(|kind| kind.is_timed()) {
+            let mut simulation = about_to_collect(kind);
+            assert_eq!(collect(&mut simulation), Side::Left, "{:?}", kind);
+            assert!(simulation.power_ups().is_empty());
+            assert_eq!(simulation.effects(), &[ActiveEffect { kind, side: Side::Left, remaining: DURATION }][..]);
+
+            let arena = simulation.arena;
+            let heights = [simulation.paddles[0].height, simulation.paddles[1].height];
+            let expected_heights = match kind {
+                PowerUpKind::Grow => [arena.paddle_height() * GROW_FACTOR, arena.paddle_height()],
+                PowerUpKind::Shrink => [arena.paddle_height(), arena.paddle_height() * SHRINK_FACTOR],
+                _ => [arena.paddle_height(); 2],
+            };
+            assert_eq!(heights, expected_heights, "{:?}", kind);
+
+            let before = simulation.balls[0].position[0];
+            simulation.step(STEP, &TickInput::default());
+            let moved = simulation.balls[0].position[0] - before;
+            let scale = match kind {
+                PowerUpKind::SpeedBoost => SPEED_BOOST_FACTOR,
+                PowerUpKind::SlowMotion => SLOW_MOTION_FACTOR,
+                _ => 1.0,
+            };
+            assert!((moved - arena.ball_velocity_x() * STEP * scale).abs() < 1e-3, "{:?} moved the ball {}", kind, moved);
+        }
+    }
+
+    #[test]
+    fn effects_wear_off_after_their_duration() {
+        let mut simulation = about_to_collect(PowerUpKind::Grow);
+        collect(&mut simulation);
+        let mut lasted = 0.0;
+        while !simulation.effects().is_empty() {
+            simulation.step(STEP, &TickInput::default());
+            lasted += STEP;
+            assert!(lasted <= DURATION + STEP, "still growing after {} seconds", lasted);
+        }
+        assert!(lasted >= DURATION - STEP);
+        assert_eq!(simulation.paddles[0].height, simulation.arena.paddle_height());
+    }
+
+    #[test]
+    fn collecting_it_again_starts_the_duration_over() {
+        let mut simulation = about_to_collect(PowerUpKind::SlowMotion);
+        collect(&mut simulation);
+        simulation.step(1.0, &TickInput::default());
+        let ball = simulation.balls[0];
+        simulation.power_ups.push(PowerUpState {
+            id: 1,
+            kind: PowerUpKind::SlowMotion,
+            position: [ball.position[0] + 20.0, ball.position[1]],
+            radius: simulation.arena.power_up_radius(),
+            expires_in: 10.0,
+        });
+        collect(&mut simulation);
+        assert_eq!(simulation.effects().len(), 1);
+        assert_eq!(simulation.effects()[0].remaining, DURATION);
+    }
+
+    #[test]
+    fn split_adds_a_ball_heading_the_other_way() {
+        let mut simulation = about_to_collect(PowerUpKind::Split);
+        collect(&mut simulation);
+        assert!(simulation.effects().is_empty());
+        let balls = simulation.balls();
+        assert_eq!(balls.len(), 2);
+        assert_ne!(balls[0].id, balls[1].id);
+        assert_eq!(balls[1].velocity, [balls[0].velocity[0], -balls[0].velocity[1]]);
+        assert_eq!(balls[1].position, balls[0].position);
+    }
+
+    #[test]
+    fn fog_hides_only_balls_in_the_ai_half() {
+        let mut simulation = about_to_collect(PowerUpKind::Fog);
+        // The left paddle is the player's when playing against the AI.
+        assert_eq!(collect(&mut simulation), Side::Left);
+        let middle = simulation.arena.width() / 2.0;
+        let mut ball = simulation.balls[0];
+        ball.position[0] = middle + 100.0;
+        assert!(simulation.is_fogged(&ball));
+        ball.position[0] = middle - 100.0;
+        assert!(!simulation.is_fogged(&ball));
+    }
+
+    #[test]
+    fn pickup_goes_to_the_paddle_the_ball_moves_away_from() {
+        let mut simulation = about_to_collect(PowerUpKind::Grow);
+        let ball = &mut simulation.balls[0];
+        ball.velocity[0] = -ball.velocity[0];
+        simulation.power_ups[0].position[0] -= 100.0;
+        assert_eq!(collect(&mut simulation), Side::Right);
+        assert_eq!(simulation.paddles[1].height, simulation.arena.paddle_height() * GROW_FACTOR);
+    }
+}
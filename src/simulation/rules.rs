@@ -0,0 +1,111 @@
+use crate::simulation::paddle::Side;
+
+/// How a match is won.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum MatchRules {
+    /// The match never ends, the longest time without conceding is what counts.
+    #[default]
+    Survival,
+    /// The first side to reach `points` wins, with `win_by_two` the lead must also be two points.
+    FirstTo { points: u32, win_by_two: bool },
+    /// The side leading after `seconds` wins, an even score is a draw.
+    TimeLimit { seconds: f32 },
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Score {
+    pub left: u32,
+    pub right: u32,
+}
+
+impl Score {
+    pub fn of(&self, side: Side) -> u32 {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
+
+    pub fn add_point(&mut self, side: Side) {
+        match side {
+            Side::Left => self.left += 1,
+            Side::Right => self.right += 1,
+        }
+    }
+
+    fn leader(&self) -> Option<Side> {
+        if self.left > self.right {
+            Some(Side::Left)
+        } else if self.right > self.left {
+            Some(Side::Right)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MatchResult {
+    Winner(Side),
+    Draw,
+}
+
+impl MatchRules {
+    /// The result of the match, or `None` while it is still being played.
+    pub fn result(&self, score: &Score, elapsed: f32) -> Option<MatchResult> {
+        match *self {
+            MatchRules::Survival => None,
+            MatchRules::FirstTo { points, win_by_two } => {
+                let leader = score.leader()?;
+                let lead = score.of(leader) - score.of(leader.opponent());
+                if score.of(leader) >= points && (!win_by_two || lead >= 2) {
+                    Some(MatchResult::Winner(leader))
+                } else {
+                    None
+                }
+            }
+            MatchRules::TimeLimit { seconds } => {
+                if elapsed < seconds {
+                    None
+                } else {
+                    Some(score.leader().map_or(MatchResult::Draw, MatchResult::Winner))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn survival_never_ends() {
+        let score = Score { left: 0, right: 99 };
+        assert_eq!(MatchRules::Survival.result(&score, 10_000.0), None);
+    }
+
+    #[test]
+    fn first_to_ends_at_the_points() {
+        let rules = MatchRules::FirstTo { points: 5, win_by_two: false };
+        assert_eq!(rules.result(&Score { left: 4, right: 4 }, 0.0), None);
+        assert_eq!(rules.result(&Score { left: 5, right: 4 }, 0.0), Some(MatchResult::Winner(Side::Left)));
+        assert_eq!(rules.result(&Score { left: 2, right: 5 }, 0.0), Some(MatchResult::Winner(Side::Right)));
+    }
+
+    #[test]
+    fn first_to_with_win_by_two_needs_a_lead_of_two() {
+        let rules = MatchRules::FirstTo { points: 5, win_by_two: true };
+        assert_eq!(rules.result(&Score { left: 5, right: 4 }, 0.0), None);
+        assert_eq!(rules.result(&Score { left: 6, right: 4 }, 0.0), Some(MatchResult::Winner(Side::Left)));
+        assert_eq!(rules.result(&Score { left: 7, right: 9 }, 0.0), Some(MatchResult::Winner(Side::Right)));
+    }
+
+    #[test]
+    fn time_limit_ends_with_the_leader_or_a_draw() {
+        let rules = MatchRules::TimeLimit { seconds: 120.0 };
+        assert_eq!(rules.result(&Score { left: 3, right: 0 }, 119.9), None);
+        assert_eq!(rules.result(&Score { left: 3, right: 0 }, 120.0), Some(MatchResult::Winner(Side::Left)));
+        assert_eq!(rules.result(&Score { left: 2, right: 2 }, 120.0), Some(MatchResult::Draw));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::simulation::arena::Arena;
+use crate::simulation::ball::{BallState, Bounce};
+use crate::simulation::collision::{Contact, sweep_circle_aabb, sweep_circle_walls};
+use crate::simulation::level::Level;
+use crate::simulation::obstacle::Obstacle;
+use crate::simulation::paddle::{PaddleState, Side};
+
+/// What a ball touched.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Surface {
+    /// The top or bottom of the arena.
+    Wall,
+    Paddle(Side),
+    /// The obstacle at this index of the arena's obstacles.
+    Obstacle(usize),
+    /// The wall beside the goal on this side, when the goal doesn't span the whole height.
+    EndWall(Side),
+    /// The goal behind the paddle on this side, the ball goes back to the middle from there.
+    Goal(Side),
+}
+
+/// Everything a ball can bounce off, the simulation and the predictor share it so predictions
+/// follow the same rules as the game.
+#[derive(Debug, Copy, Clone)]
+pub struct Surroundings<'a> {
+    pub arena: Arena,
+    pub paddles: &'a [PaddleState],
+    /// Where the obstacles of `level` are now.
+    pub obstacles: &'a [Obstacle],
+    pub level: &'a Level,
+    pub bounce: Bounce,
+    /// Seconds into the match, moving obstacles are placed by it.
+    pub elapsed: f32,
+    /// Seconds of the game balls moved for since they were last checked, moving obstacles moved along with them.
+    pub step: f32,
+    /// How much faster than their velocity balls move, by the effects of power-ups.
+    pub time_scale: f32,
+}
+
+impl<'a> Surroundings<'a> {
+    /// The first surface the ball touched on its way from `previous_position` to `position`.
+    ///
+    /// Only surfaces the ball is heading into count, and a paddle wins a tie with anything else.
+    pub fn first_contact(&self, ball: &BallState) -> Option<(Surface, Contact)> {
+        let from = ball.previous_position;
+        let to = ball.position;
+        let velocity = ball.velocity;
+        let paddles = self.paddles.iter()
+            .filter(|paddle| ball.is_heading_to(paddle.side))
+            .filter_map(|paddle| {
+                let (min, max) = paddle.bounds();
+                sweep_with(Surface::Paddle(paddle.side), velocity, sweep_circle_aabb(from, to, ball.radius, min, max))
+            });
+        let wall = sweep_with(Surface::Wall, velocity, sweep_circle_walls(from, to, ball.radius, 0.0, self.arena.height()));
+        // Moving obstacles are swept as if they stood still where they are now, with the ball
+        // starting as far from them as it was before they moved.
+        let obstacles = self.obstacles.iter()
+            .enumerate()
+            .filter_map(|(index, obstacle)| {
+                let (shift, obstacle_velocity) = self.obstacle_motion(index);
+                let from = [from[0] + shift[0], from[1] + shift[1]];
+                let relative_velocity = [velocity[0] - obstacle_velocity[0], velocity[1] - obstacle_velocity[1]];
+                sweep_with(self.obstacle_surface(index), relative_velocity, obstacle.sweep(from, to, ball.radius))
+            });
+        paddles.chain(wall).chain(obstacles)
+            .fold(None, |first: Option<(Surface, Contact)>, hit| match first {
+                Some(first) if first.1.time <= hit.1.time => Some(first),
+                _ => Some(hit),
+            })
+    }
+
+    /// Moves the ball to where it touched `surface` and sends it off again.
+    pub fn bounce_off(&self, ball: &mut BallState, surface: Surface, contact: &Contact) {
+        ball.position = contact.point;
+        match surface {
+            Surface::Wall => {
+                ball.velocity[1] = -ball.velocity[1] * self.level.bounciness;
+                if self.level.bounciness > 1.0 {
+                    let max_velocity = self.arena.max_velocity();
+                    ball.velocity[1] = ball.velocity[1].clamp(-max_velocity, max_velocity);
+                }
+                ball.spin = -ball.spin;
+            }
+            Surface::Paddle(side) => {
+                // Glancing off the top or bottom of a paddle also turns the ball around.
+                if contact.normal[1] * ball.velocity[1] < 0.0 {
+                    ball.velocity[1] = -ball.velocity[1];
+                }
+                if let Some(paddle) = self.paddles.iter().find(|paddle| paddle.side == side) {
+                    ball.return_from_paddle(paddle, self.bounce, &self.arena);
+                }
+            }
+            Surface::Obstacle(_) | Surface::EndWall(_) => {
+                let normal = contact.normal;
+                let obstacle_velocity = match surface {
+                    Surface::Obstacle(index) => self.obstacle_motion(index).1,
+                    _ => [0.0, 0.0],
+                };
+                let along_normal = (ball.velocity[0] - obstacle_velocity[0]) * normal[0] + (ball.velocity[1] - obstacle_velocity[1]) * normal[1];
+                let max_velocity = self.arena.max_velocity();
+                let velocity = [
+                    (ball.velocity[0] - 2.0 * along_normal * normal[0]).clamp(-max_velocity, max_velocity),
+                    (ball.velocity[1] - 2.0 * along_normal * normal[1]).clamp(-max_velocity, max_velocity),
+                ];
+                let turned_around = ball.velocity[0] * velocity[0] < 0.0;
+                ball.velocity = velocity;
+                // Spin only curves vertically, it is mirrored as much as the surface faces up or down.
+                ball.spin *= 1.0 - 2.0 * normal[1] * normal[1];
+                if turned_around {
+                    ball.calculated_impact_y = None;
+                }
+            }
+            Surface::Goal(_) => {}
+        }
+    }
+
+    /// The obstacles of the level come first, the walls beside the goals after them.
+    fn obstacle_surface(&self, index: usize) -> Surface {
+        if index < self.level.obstacles.len() {
+            Surface::Obstacle(index)
+        } else if self.obstacles[index].center()[0] < self.arena.width() / 2.0 {
+            Surface::EndWall(Side::Left)
+        } else {
+            Surface::EndWall(Side::Right)
+        }
+    }
+
+    /// How far the obstacle at `index` moved since balls were last checked, and its velocity in
+    /// the units of ball velocities.
+    fn obstacle_motion(&self, index: usize) -> ([f32; 2], [f32; 2]) {
+        let shift = self.level.shift(index, self.elapsed, self.step);
+        if self.step <= 0.0 || self.time_scale <= 0.0 {
+            return (shift, [0.0, 0.0]);
+        }
+        let seconds = self.step * self.time_scale;
+        (shift, [shift[0] / seconds, shift[1] / seconds])
+    }
+}
+
+fn sweep_with(surface: Surface, velocity: [f32; 2], contact: Option<Contact>) -> Option<(Surface, Contact)> {
+    contact
+        .filter(|contact| contact.is_approached_by(velocity))
+        .map(|contact| (surface, contact))
+}
+
+/// Where a predicted path touches something.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PredictedContact {
+    /// Seconds of the game from now, at the speed the ball moves with the current effects.
+    pub time: f32,
+    /// Center of the ball when it touches.
+    pub point: [f32; 2],
+    /// Velocity of the ball after bouncing off.
+    pub velocity: [f32; 2],
+    pub surface: Surface,
+}
+
+/// How far ahead and how finely a path is predicted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PredictionLimits {
+    /// Seconds to look ahead.
+    pub horizon: f32,
+    /// Seconds between the points the path is checked at. Only a step equal to the one the game
+    /// moves balls by sees exactly the contacts the game will, the default is the usual fixed step.
+    pub step: f32,
+    /// Stops after this many contacts.
+    pub max_contacts: usize,
+}
+
+impl Default for PredictionLimits {
+    fn default() -> Self {
+        PredictionLimits {
+            horizon: 10.0,
+            step: 1.0 / 120.0,
+            max_contacts: 32,
+        }
+    }
+}
+
+/// Steps `ball` through `surroundings` like the simulation would, and lists everything it touches
+/// until it reaches a goal or the limits are hit.
+///
+/// Paddles keep moving at the speed of their last move until they reach a wall, pass no paddles
+/// to predict where the ball reaches the goal lines. Obstacles follow their motion, power-ups are
+/// ignored and so are changes to the speed of the game once their effects wear off.
+pub fn predict(ball: &BallState, surroundings: &Surroundings, limits: PredictionLimits) -> Vec<PredictedContact> {
+    let mut contacts = vec![];
+    if limits.step <= 0.0 {
+        return contacts;
+    }
+    let mut ball = *ball;
+    let mut paddles = surroundings.paddles.to_vec();
+    let mut obstacles = surroundings.obstacles.to_vec();
+    let moving_obstacles = surroundings.level.has_moving_obstacles();
+    let mut time = 0.0;
+    while time < limits.horizon && contacts.len() < limits.max_contacts {
+        for (paddle, now) in paddles.iter_mut().zip(surroundings.paddles) {
+            paddle.y = now.extrapolate(time + limits.step, &surroundings.arena);
+        }
+        if moving_obstacles {
+            surroundings.level.move_obstacles(surroundings.elapsed + time + limits.step, &mut obstacles);
+        }
+        let here = Surroundings {
+            paddles: &paddles,
+            obstacles: &obstacles,
+            elapsed: surroundings.elapsed + time + limits.step,
+            step: limits.step,
+            ..*surroundings
+        };
+        ball.advance(limits.step * surroundings.time_scale);
+        if let Some((surface, contact)) = here.first_contact(&ball) {
+            here.bounce_off(&mut ball, surface, &contact);
+            contacts.push(PredictedContact {
+                time: time + contact.time * limits.step,
+                point: ball.position,
+                velocity: ball.velocity,
+                surface,
+            });
+        }
+        time += limits.step;
+        if let Some(side) = ball.goal(&surroundings.arena) {
+            contacts.push(PredictedContact { time, point: ball.position, velocity: ball.velocity, surface: Surface::Goal(side) });
+            break;
+        }
+    }
+    contacts
+}
@@ -0,0 +1,166 @@
+use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
+use amethyst::core::ecs::{Entity, World, WorldExt, WriteStorage};
+use amethyst::input::{is_close_requested, is_key_down, Button, InputEvent, InputHandler, StringBindings, VirtualKeyCode};
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
+
+use crate::controls::{bound_buttons, button_name, persist_async, rebind, Rebindable, RebindError, REBINDABLE};
+use crate::state::options::OptionState;
+
+const STATUS_LBL: &str = "controls_status";
+const BACK_BTN: &str = "back";
+
+/// Lists the rebindable controls, and binds the next key or gamepad button pressed after one is
+/// clicked.
+#[derive(Default)]
+pub struct ControlsState {
+    root: Option<Entity>,
+    status_lbl: Option<Entity>,
+    back_btn: Option<Entity>,
+    binding_btns: Vec<(Rebindable, Entity)>,
+    /// The control waiting for a key or button.
+    capturing: Option<Rebindable>,
+    /// Set once the press that started the capture has been handled, so it is not captured itself.
+    armed: bool,
+    needs_refresh: bool,
+}
+
+impl SimpleState for ControlsState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        self.root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/controls.ron", ())));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(root) = self.root {
+            if data.world.delete_entity(root).is_ok() {
+                self.root = None;
+            }
+        }
+        self.status_lbl = None;
+        self.back_btn = None;
+        self.binding_btns.clear();
+        self.capturing = None;
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    if self.capturing.take().is_some() {
+                        set_text(world, self.status_lbl, String::new());
+                        Trans::None
+                    } else {
+                        log::info!("[Trans::Switch] Switching back to Options!");
+                        Trans::Switch(Box::new(OptionState::default()))
+                    }
+                } else {
+                    Trans::None
+                }
+            }
+            StateEvent::Ui(UiEvent {
+                               event_type: UiEventType::Click,
+                               target,
+                           }) => {
+                if self.capturing.is_some() {
+                    return Trans::None;
+                }
+                if Some(target) == self.back_btn {
+                    return Trans::Switch(Box::new(OptionState::default()));
+                }
+                if let Some((rebindable, _)) = self.binding_btns.iter().find(|(_, entity)| *entity == target) {
+                    self.capturing = Some(*rebindable);
+                    self.armed = false;
+                    set_text(world, self.status_lbl, format!("PRESS A KEY OR BUTTON FOR {}", rebindable.name));
+                }
+                Trans::None
+            }
+            StateEvent::Input(InputEvent::ButtonPressed(button)) => {
+                let capture = match button {
+                    Button::Key(VirtualKeyCode::Escape) => false,
+                    Button::Key(_) | Button::Controller(_, _) => self.armed,
+                    _ => false,
+                };
+                if let (true, Some(rebindable)) = (capture, self.capturing) {
+                    self.capturing = None;
+                    self.capture(world, rebindable, button);
+                }
+                Trans::None
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+        if self.status_lbl.is_none() || self.back_btn.is_none() || self.binding_btns.len() < REBINDABLE.len() {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                self.status_lbl = ui_finder.find(STATUS_LBL);
+                self.back_btn = ui_finder.find(BACK_BTN);
+                self.binding_btns = REBINDABLE.iter()
+                    .filter_map(|rebindable| ui_finder.find(rebindable.id).map(|entity| (*rebindable, entity)))
+                    .collect();
+            });
+            self.needs_refresh = true;
+        }
+        if self.needs_refresh {
+            self.needs_refresh = !self.refresh_bindings(world);
+        }
+        self.armed = self.capturing.is_some();
+        Trans::None
+    }
+}
+
+impl ControlsState {
+    fn capture(&mut self, world: &mut World, rebindable: Rebindable, button: Button) {
+        let status = {
+            let mut input = world.write_resource::<InputHandler<StringBindings>>();
+            match rebind(&mut input.bindings, rebindable.control, button) {
+                Ok(()) => {
+                    persist_async(&input.bindings);
+                    log::info!("Bound {:?} to {:?}", button, rebindable.control);
+                    String::new()
+                }
+                Err(RebindError::Conflict(other)) => format!("{} IS ALREADY USED BY {}", button_name(button), other),
+                Err(RebindError::UnsupportedDevice) => format!("{} CAN'T BE USED FOR {}", button_name(button), rebindable.name),
+            }
+        };
+        set_text(world, self.status_lbl, status);
+        self.needs_refresh = true;
+    }
+
+    /// Shows the current bindings on the buttons, false while the button texts are not created yet.
+    fn refresh_bindings(&self, world: &mut World) -> bool {
+        let input = world.read_resource::<InputHandler<StringBindings>>();
+        let texts_by_id: Vec<_> = self.binding_btns.iter()
+            .map(|(rebindable, _)| {
+                let text = bound_buttons(&input.bindings, rebindable.control).into_iter()
+                    .map(button_name)
+                    .collect::<Vec<_>>()
+                    .join(" / ");
+                (format!("{}_btn_txt", rebindable.id), text)
+            })
+            .collect();
+        drop(input);
+        world.exec(|(ui_finder, mut texts): (UiFinder<'_>, WriteStorage<UiText>)| {
+            let mut all_found = !texts_by_id.is_empty();
+            for (id, text) in texts_by_id {
+                match ui_finder.find(&id).and_then(|entity| texts.get_mut(entity)) {
+                    Some(ui_text) => ui_text.text = text,
+                    None => all_found = false,
+                }
+            }
+            all_found
+        })
+    }
+}
+
+fn set_text(world: &mut World, entity: Option<Entity>, text: String) {
+    let mut texts = world.write_component::<UiText>();
+    if let Some(ui_text) = entity.and_then(|entity| texts.get_mut(entity)) {
+        ui_text.text = text;
+    }
+}
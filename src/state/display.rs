@@ -0,0 +1,138 @@
+use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
+use amethyst::core::ecs::{Entity, World, WorldExt, WriteStorage};
+use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
+
+use crate::display::{apply_display_settings, monitor_count, FPS_CAPS};
+use crate::persistence::{DisplaySettings, Settings};
+use crate::state::options::OptionState;
+
+const WINDOW_MODE_BTN: &str = "window_mode";
+const MONITOR_BTN: &str = "monitor";
+const FPS_CAP_BTN: &str = "fps_cap";
+const BACK_BTN: &str = "back";
+
+/// Window mode, monitor and frame rate cap, each button cycles through the choices and applies
+/// them right away.
+#[derive(Default)]
+pub struct DisplayState {
+    root: Option<Entity>,
+    window_mode_btn: Option<Entity>,
+    monitor_btn: Option<Entity>,
+    fps_cap_btn: Option<Entity>,
+    back_btn: Option<Entity>,
+    needs_refresh: bool,
+}
+
+impl SimpleState for DisplayState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        self.root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/display.ron", ())));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(root) = self.root {
+            if data.world.delete_entity(root).is_ok() {
+                self.root = None;
+            }
+        }
+        self.window_mode_btn = None;
+        self.monitor_btn = None;
+        self.fps_cap_btn = None;
+        self.back_btn = None;
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    log::info!("[Trans::Switch] Switching back to Options!");
+                    Trans::Switch(Box::new(OptionState::default()))
+                } else {
+                    Trans::None
+                }
+            }
+            StateEvent::Ui(UiEvent {
+                               event_type: UiEventType::Click,
+                               target,
+                           }) => {
+                let target = Some(target);
+                if target == self.back_btn {
+                    return Trans::Switch(Box::new(OptionState::default()));
+                }
+                let monitors = monitor_count(world);
+                if target == self.window_mode_btn {
+                    change_display_settings(world, |display| display.window_mode = display.window_mode.next());
+                } else if target == self.monitor_btn {
+                    change_display_settings(world, |display| display.monitor = (display.monitor + 1) % monitors);
+                } else if target == self.fps_cap_btn {
+                    change_display_settings(world, |display| display.fps_cap = next_fps_cap(display.fps_cap));
+                }
+                self.needs_refresh = true;
+                Trans::None
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+        if self.window_mode_btn.is_none() || self.monitor_btn.is_none() || self.fps_cap_btn.is_none() || self.back_btn.is_none() {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                self.window_mode_btn = ui_finder.find(WINDOW_MODE_BTN);
+                self.monitor_btn = ui_finder.find(MONITOR_BTN);
+                self.fps_cap_btn = ui_finder.find(FPS_CAP_BTN);
+                self.back_btn = ui_finder.find(BACK_BTN);
+            });
+            self.needs_refresh = true;
+        }
+        if self.needs_refresh {
+            self.needs_refresh = !refresh_labels(world);
+        }
+        Trans::None
+    }
+}
+
+fn change_display_settings(world: &mut World, change: impl FnOnce(&mut DisplaySettings)) {
+    let display_settings = {
+        let mut settings = world.write_resource::<Settings>();
+        change(&mut settings.display_settings);
+        settings.persist_async();
+        settings.display_settings
+    };
+    log::info!("Changed display settings to {:?}", display_settings);
+    apply_display_settings(world, &display_settings);
+}
+
+fn next_fps_cap(fps_cap: u32) -> u32 {
+    let index = FPS_CAPS.iter().position(|cap| *cap == fps_cap).map_or(0, |index| index + 1);
+    FPS_CAPS[index % FPS_CAPS.len()]
+}
+
+/// Shows the current choices on the buttons, false while the button texts are not created yet.
+fn refresh_labels(world: &mut World) -> bool {
+    let display = world.read_resource::<Settings>().display_settings;
+    let fps_cap = match display.fps_cap {
+        0 => "FPS UNLIMITED".to_string(),
+        fps_cap => format!("FPS {}", fps_cap),
+    };
+    let labels = [
+        (WINDOW_MODE_BTN, display.window_mode.name().to_string()),
+        (MONITOR_BTN, format!("MONITOR {}", display.monitor + 1)),
+        (FPS_CAP_BTN, fps_cap),
+    ];
+    world.exec(|(ui_finder, mut texts): (UiFinder<'_>, WriteStorage<UiText>)| {
+        let mut all_found = true;
+        for (id, label) in labels.iter() {
+            match ui_finder.find(&format!("{}_btn_txt", id)).and_then(|entity| texts.get_mut(entity)) {
+                Some(ui_text) => ui_text.text = label.clone(),
+                None => all_found = false,
+            }
+        }
+        all_found
+    })
+}
@@ -0,0 +1,102 @@
+use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
+use amethyst::core::ecs::{Entity, World, WorldExt};
+use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
+
+use crate::highscore::{self, HighScores};
+use crate::level::Levels;
+use crate::persistence::Settings;
+use crate::simulation::power_up::PowerUpRules;
+use crate::state::main_menu::MainMenu;
+
+const SCORES_LABEL: &str = "scores";
+const BACK_BTN: &str = "back";
+
+/// The table of the longest survival times against the AI.
+#[derive(Default)]
+pub struct HighScoresState {
+    root: Option<Entity>,
+    scores_label: Option<Entity>,
+    back_btn: Option<Entity>,
+}
+
+impl SimpleState for HighScoresState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        self.root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/high_scores.ron", ())));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(root) = self.root {
+            if data.world.delete_entity(root).is_ok() {
+                self.root = None;
+            }
+        }
+        self.scores_label = None;
+        self.back_btn = None;
+    }
+
+    fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    log::info!("[Trans::Switch] Switching back to MainMenu!");
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else {
+                    Trans::None
+                }
+            }
+            StateEvent::Ui(UiEvent {
+                               event_type: UiEventType::Click,
+                               target,
+                           }) if Some(target) == self.back_btn => {
+                log::info!("[Trans::Switch] Switching back to MainMenu!");
+                Trans::Switch(Box::new(MainMenu::default()))
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+        if self.scores_label.is_none() || self.back_btn.is_none() {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                self.scores_label = ui_finder.find(SCORES_LABEL);
+                self.back_btn = ui_finder.find(BACK_BTN);
+            });
+            if let Some(scores_label) = self.scores_label {
+                let text = scores_text(world);
+                if let Some(ui_text) = world.write_storage::<UiText>().get_mut(scores_label) {
+                    ui_text.text = text;
+                }
+            }
+        }
+        Trans::None
+    }
+}
+
+/// One line per high score, times set with other settings than the current ones are marked with a `*`.
+fn scores_text(world: &World) -> String {
+    let high_scores = world.read_resource::<HighScores>();
+    if high_scores.entries().is_empty() {
+        return "NO RECORDS YET".to_string();
+    }
+    let settings = world.read_resource::<Settings>();
+    let levels = world.read_resource::<Levels>();
+    let fingerprint = highscore::fingerprint(&settings, &world.read_resource::<PowerUpRules>(), levels.get(settings.level.as_deref()));
+    high_scores.entries().iter()
+        .enumerate()
+        .map(|(rank, entry)| format!(
+            "{:>2}. {:>7.1}s  {:<10} {}{}",
+            rank + 1,
+            entry.seconds,
+            entry.difficulty.name(),
+            entry.date,
+            if entry.fingerprint == fingerprint { "" } else { " *" },
+        ))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
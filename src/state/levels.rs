@@ -0,0 +1,176 @@
+use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
+use amethyst::assets::Handle;
+use amethyst::core::ecs::{Entity, World, WorldExt};
+use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
+use amethyst::renderer::SpriteSheet;
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
+
+use crate::level::Levels;
+use crate::persistence::Settings;
+use crate::simulation::level::Level;
+use crate::state::players::PlayersState;
+
+const NAME_LABEL: &str = "level_name";
+const INFO_LABEL: &str = "level_info";
+const PREVIOUS_BTN: &str = "previous";
+const NEXT_BTN: &str = "next";
+const SELECT_BTN: &str = "select";
+const BACK_BTN: &str = "back";
+
+/// Browses the levels one at a time and picks the one matches are played in.
+pub struct LevelsState {
+    sprite_sheet_handle: Handle<SpriteSheet>,
+    root: Option<Entity>,
+    name_label: Option<Entity>,
+    info_label: Option<Entity>,
+    previous_btn: Option<Entity>,
+    next_btn: Option<Entity>,
+    select_btn: Option<Entity>,
+    back_btn: Option<Entity>,
+    /// Position of the level shown in `Levels`.
+    shown: usize,
+}
+
+impl LevelsState {
+    pub fn new(sprite_sheet_handle: Handle<SpriteSheet>) -> Self {
+        LevelsState {
+            sprite_sheet_handle,
+            root: None,
+            name_label: None,
+            info_label: None,
+            previous_btn: None,
+            next_btn: None,
+            select_btn: None,
+            back_btn: None,
+            shown: 0,
+        }
+    }
+
+    fn back(&self) -> SimpleTrans {
+        log::info!("[Trans::Switch] Switching back to Players!");
+        Trans::Switch(Box::new(PlayersState::new(self.sprite_sheet_handle.clone())))
+    }
+
+    fn select(&self, world: &mut World) -> SimpleTrans {
+        let id = world.read_resource::<Levels>().entries()[self.shown].id.clone();
+        let mut settings = world.write_resource::<Settings>();
+        log::info!("Level set to {:?}", id);
+        settings.level = id;
+        settings.persist_async();
+        self.back()
+    }
+
+    /// Shows the level `by` places after the one shown now, going around at either end.
+    fn browse(&mut self, world: &mut World, by: isize) {
+        let count = world.read_resource::<Levels>().entries().len() as isize;
+        self.shown = (self.shown as isize + by).rem_euclid(count) as usize;
+        self.show(world);
+    }
+
+    fn show(&self, world: &mut World) {
+        let levels = world.read_resource::<Levels>();
+        let level = &levels.entries()[self.shown].level;
+        let mut texts = world.write_storage::<UiText>();
+        if let Some(text) = self.name_label.and_then(|label| texts.get_mut(label)) {
+            text.text = level.name.to_uppercase();
+        }
+        if let Some(text) = self.info_label.and_then(|label| texts.get_mut(label)) {
+            text.text = level_info(level);
+        }
+    }
+}
+
+impl SimpleState for LevelsState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        let level = world.read_resource::<Settings>().level.clone();
+        self.shown = world.read_resource::<Levels>().position(level.as_deref());
+        self.root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/levels.ron", ())));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(root) = self.root {
+            if data.world.delete_entity(root).is_ok() {
+                self.root = None;
+            }
+        }
+        self.name_label = None;
+        self.info_label = None;
+        self.previous_btn = None;
+        self.next_btn = None;
+        self.select_btn = None;
+        self.back_btn = None;
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    self.back()
+                } else if is_key_down(&event, VirtualKeyCode::Left) {
+                    self.browse(data.world, -1);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Right) {
+                    self.browse(data.world, 1);
+                    Trans::None
+                } else {
+                    Trans::None
+                }
+            }
+            StateEvent::Ui(UiEvent {
+                               event_type: UiEventType::Click,
+                               target,
+                           }) => {
+                if Some(target) == self.previous_btn {
+                    self.browse(data.world, -1);
+                    Trans::None
+                } else if Some(target) == self.next_btn {
+                    self.browse(data.world, 1);
+                    Trans::None
+                } else if Some(target) == self.select_btn {
+                    self.select(data.world)
+                } else if Some(target) == self.back_btn {
+                    self.back()
+                } else {
+                    Trans::None
+                }
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.name_label.is_none()
+            || self.info_label.is_none()
+            || self.previous_btn.is_none()
+            || self.next_btn.is_none()
+            || self.select_btn.is_none()
+            || self.back_btn.is_none()
+        {
+            data.world.exec(|ui_finder: UiFinder<'_>| {
+                self.name_label = ui_finder.find(NAME_LABEL);
+                self.info_label = ui_finder.find(INFO_LABEL);
+                self.previous_btn = ui_finder.find(PREVIOUS_BTN);
+                self.next_btn = ui_finder.find(NEXT_BTN);
+                self.select_btn = ui_finder.find(SELECT_BTN);
+                self.back_btn = ui_finder.find(BACK_BTN);
+            });
+            self.show(data.world);
+        }
+        Trans::None
+    }
+}
+
+fn level_info(level: &Level) -> String {
+    let moving = level.obstacles.iter().filter(|obstacle| obstacle.motion.is_some()).count();
+    format!(
+        "GOALS: {:.0}%\nOBSTACLES: {} ({} MOVING)\nBOUNCINESS: {:.2}",
+        level.goal_size.clamp(0.0, 1.0) * 100.0,
+        level.obstacles.len(),
+        moving,
+        level.bounciness,
+    )
+}
@@ -1,20 +1,29 @@
 use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
 use amethyst::assets::{Handle, Loader, AssetStorage};
-use amethyst::core::ecs::{Entity, WorldExt, World, Builder};
+use amethyst::core::ecs::{Entity, WorldExt, World, Builder, ReadExpect, WriteStorage};
 use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
 use amethyst::renderer::{SpriteSheet, Texture, ImageFormat, SpriteRender, Sprite};
-use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder};
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
 
-use crate::state::pong::{Pong};
 use crate::state::start::StartScreen;
 use crate::taunt::{TauntComponent, Taunt};
 use amethyst::core::Transform;
 use crate::state::options::OptionState;
-use crate::persistence::window::WindowSettings;
+use crate::state::players::PlayersState;
+use crate::state::replay::watch_last_replay;
+use crate::state::high_scores::HighScoresState;
+use crate::state::stats::StatsState;
+use crate::simulation::arena::ARENA;
 use amethyst::core::math::Vector3;
 use crate::persistence::Settings;
+use crate::simulation::ai::Difficulty;
 
 const BUTTON_START: &str = "start";
+const BUTTON_DIFFICULTY: &str = "difficulty";
+const DIFFICULTY_TEXT: &str = "difficulty_btn_txt";
+const BUTTON_REPLAY: &str = "replay";
+const BUTTON_HIGH_SCORES: &str = "high_scores";
+const BUTTON_STATS: &str = "stats";
 const BUTTON_OPTIONS: &str = "options";
 const BUTTON_EXIT: &str = "exit";
 
@@ -23,6 +32,11 @@ pub struct MainMenu {
     sprite_sheet: Option<Handle<SpriteSheet>>,
     ui_root: Option<Entity>,
     button_start: Option<Entity>,
+    button_difficulty: Option<Entity>,
+    difficulty_text: Option<Entity>,
+    button_replay: Option<Entity>,
+    button_high_scores: Option<Entity>,
+    button_stats: Option<Entity>,
     button_options: Option<Entity>,
     button_exit: Option<Entity>,
 }
@@ -32,13 +46,10 @@ impl SimpleState for MainMenu {
     fn on_start(&mut self, data: StateData<'_, GameData>) {
         // create UI from prefab and save the reference.
         let world = data.world;
-        let read = world.read_resource::<Settings>();
-        let settings = *read;
-        drop(read);
         let sprite_sheet = load_sprite_sheet(world);
         self.sprite_sheet.replace(sprite_sheet.clone());
 
-        initialize_taunt(world, sprite_sheet.clone(), settings.window_settings);
+        initialize_taunt(world, sprite_sheet.clone());
         self.ui_root =
             Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/menu.ron", ())));
     }
@@ -53,6 +64,11 @@ impl SimpleState for MainMenu {
 
         self.ui_root = None;
         self.button_start = None;
+        self.button_difficulty = None;
+        self.difficulty_text = None;
+        self.button_replay = None;
+        self.button_high_scores = None;
+        self.button_stats = None;
         self.button_options = None;
         self.button_exit = None;
     }
@@ -65,7 +81,7 @@ impl SimpleState for MainMenu {
                     Trans::Quit
                 } else if is_key_down(&event, VirtualKeyCode::Escape) {
                     log::info!("[Trans::Switch] Switching back to WelcomeScreen!");
-                    Trans::Switch(Box::new(StartScreen::new(*data.world.read_resource::<Settings>())))
+                    Trans::Switch(Box::new(StartScreen::new((*data.world.read_resource::<Settings>()).clone())))
                 } else {
                     Trans::None
                 }
@@ -75,8 +91,31 @@ impl SimpleState for MainMenu {
                                target,
                            }) => {
                 if Some(target) == self.button_start {
-                    log::info!("[Trans::Switch] Switching to Game!");
-                    return Trans::Switch(Box::new(Pong::new(self.sprite_sheet.clone().unwrap(), data.world.read_resource::<Settings>().window_settings)));
+                    log::info!("[Trans::Switch] Switching to Players menu!");
+                    return Trans::Switch(Box::new(PlayersState::new(self.sprite_sheet.clone().unwrap())));
+                }
+                if Some(target) == self.button_difficulty {
+                    let mut settings = data.world.write_resource::<Settings>();
+                    settings.difficulty = settings.difficulty.next();
+                    settings.persist_async();
+                    log::info!("Difficulty set to {:?}", settings.difficulty);
+                    if let Some(text) = self.difficulty_text {
+                        if let Some(ui_text) = data.world.write_component::<UiText>().get_mut(text) {
+                            ui_text.text = difficulty_label(settings.difficulty);
+                        }
+                    }
+                    return Trans::None;
+                }
+                if Some(target) == self.button_replay {
+                    return watch_last_replay(self.sprite_sheet.clone().unwrap());
+                }
+                if Some(target) == self.button_high_scores {
+                    log::info!("[Trans::Switch] Switching to HighScores!");
+                    return Trans::Switch(Box::new(HighScoresState::default()));
+                }
+                if Some(target) == self.button_stats {
+                    log::info!("[Trans::Switch] Switching to Statistics!");
+                    return Trans::Switch(Box::new(StatsState::default()));
                 }
                 if Some(target) == self.button_options {
                     return Trans::Switch(Box::new(OptionState::default()));
@@ -96,13 +135,26 @@ impl SimpleState for MainMenu {
         let StateData { world, .. } = state_data;
 
         if self.button_start.is_none()
+            || self.button_difficulty.is_none()
+            || self.difficulty_text.is_none()
+            || self.button_replay.is_none()
+            || self.button_high_scores.is_none()
+            || self.button_stats.is_none()
             || self.button_options.is_none()
             || self.button_exit.is_none()
         {
-            world.exec(|ui_finder: UiFinder<'_>| {
+            world.exec(|(ui_finder, settings, mut write): (UiFinder<'_>, ReadExpect<Settings>, WriteStorage<UiText>)| {
                 self.button_start = ui_finder.find(BUTTON_START);
+                self.button_difficulty = ui_finder.find(BUTTON_DIFFICULTY);
+                self.difficulty_text = ui_finder.find(DIFFICULTY_TEXT);
+                self.button_replay = ui_finder.find(BUTTON_REPLAY);
+                self.button_high_scores = ui_finder.find(BUTTON_HIGH_SCORES);
+                self.button_stats = ui_finder.find(BUTTON_STATS);
                 self.button_options = ui_finder.find(BUTTON_OPTIONS);
                 self.button_exit = ui_finder.find(BUTTON_EXIT);
+                if let Some(difficulty_text) = self.difficulty_text {
+                    write.get_mut(difficulty_text).unwrap().text = difficulty_label(settings.difficulty);
+                }
             });
         }
 
@@ -110,8 +162,11 @@ impl SimpleState for MainMenu {
     }
 }
 
+fn difficulty_label(difficulty: Difficulty) -> String {
+    format!("AI: {}", difficulty.name())
+}
 
-fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
+pub(crate) fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
     // Load the sprite sheet necessary to render the graphics.
     // The texture is the pixel data
     // `texture_handle` is a cloneable reference to the texture
@@ -136,6 +191,16 @@ fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
                 to_sprite(256, 256, 0, 0),
                 to_sprite(256, 256, 256, 0),
                 to_sprite(256, 256, 512, 0),
+                // The power-ups, in the order of `PowerUpKind::ALL`.
+                to_sprite(32, 32, 1024, 64),
+                to_sprite(32, 32, 1064, 64),
+                to_sprite(32, 32, 1104, 64),
+                to_sprite(32, 32, 1144, 64),
+                to_sprite(32, 32, 1184, 64),
+                to_sprite(32, 32, 1224, 64),
+                // The obstacles of levels, a block and a disc.
+                to_sprite(32, 32, 1064, 0),
+                to_sprite(32, 32, 1104, 0),
             ]
         };
         let loader = world.read_resource::<Loader>();
@@ -148,15 +213,15 @@ fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
 
 fn to_sprite(width: u32, height: u32, x: u32, y: u32) -> Sprite {
     Sprite::from_pixel_values(
-        1280, 256, width, height as u32, x, y,
+        1280, 256, width, height, x, y,
         [0f32, 0.0], false, false)
 }
 
-fn initialize_taunt(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>, window_settings: WindowSettings) {
+fn initialize_taunt(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) {
     world.register::<TauntComponent>();
     let mut taunt_transform = Transform::default();
-    taunt_transform.set_translation_xyz(window_settings.arena_width() / 2.0, window_settings.arena_height() - window_settings.taunt_height() / 2.0, -1.0);
-    taunt_transform.set_scale(Vector3::new(window_settings.taunt_scale(), window_settings.taunt_scale(), 1.0));
+    taunt_transform.set_translation_xyz(ARENA.width() / 2.0, ARENA.height() - ARENA.taunt_height() / 2.0, -1.0);
+    taunt_transform.set_scale(Vector3::new(ARENA.taunt_scale(), ARENA.taunt_scale(), 1.0));
     let sprite = SpriteRender::new(sprite_sheet_handle, 3);
     let taunt = world.create_entity()
         .with(taunt_transform)
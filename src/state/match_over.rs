@@ -0,0 +1,149 @@
+use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
+use amethyst::assets::Handle;
+use amethyst::core::ecs::{Entity, WorldExt, WriteStorage};
+use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
+use amethyst::renderer::SpriteSheet;
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
+
+use crate::paddle::component::Side;
+use crate::persistence::Settings;
+use crate::score::score_system::format_score;
+use crate::simulation::controller::Players;
+use crate::simulation::rules::{MatchResult, Score};
+use crate::state::main_menu::MainMenu;
+use crate::replay::Replay;
+use crate::state::pong::Pong;
+use crate::state::replay::{watch_last_replay, ReplayState};
+
+const RESULT_LBL: &str = "result";
+const SCORE_LBL: &str = "final_score";
+const REMATCH_BUTTON_ID: &str = "rematch";
+const WATCH_REPLAY_BUTTON_ID: &str = "watch_replay";
+const EXIT_TO_MAIN_MENU_BUTTON_ID: &str = "exit_to_main_menu";
+
+pub struct MatchOverState {
+    sprite_sheet_handle: Handle<SpriteSheet>,
+    result: MatchResult,
+    score: Score,
+    replay: Option<Replay>,
+    root: Option<Entity>,
+    result_lbl: Option<Entity>,
+    score_lbl: Option<Entity>,
+    rematch_button: Option<Entity>,
+    watch_replay_button: Option<Entity>,
+    exit_to_main_menu_button: Option<Entity>,
+}
+
+impl MatchOverState {
+    pub fn new(sprite_sheet_handle: Handle<SpriteSheet>, result: MatchResult, score: Score, replay: Option<Replay>) -> Self {
+        MatchOverState {
+            sprite_sheet_handle,
+            result,
+            score,
+            replay,
+            root: None,
+            result_lbl: None,
+            score_lbl: None,
+            rematch_button: None,
+            watch_replay_button: None,
+            exit_to_main_menu_button: None,
+        }
+    }
+}
+
+impl SimpleState for MatchOverState {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        self.root =
+            Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/match_over.ron", ())));
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        if let Some(root) = self.root {
+            if data.world.delete_entity(root).is_ok() {
+                self.root = None;
+            }
+        }
+        self.result_lbl = None;
+        self.score_lbl = None;
+        self.rematch_button = None;
+        self.watch_replay_button = None;
+        self.exit_to_main_menu_button = None;
+    }
+
+    fn handle_event(&mut self, _data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    log::info!("[Trans::Switch] Switching to MainMenu!");
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else {
+                    Trans::None
+                }
+            }
+            StateEvent::Ui(UiEvent {
+                               event_type: UiEventType::Click,
+                               target,
+                           }) => {
+                if Some(target) == self.rematch_button {
+                    log::info!("[Trans::Switch] Switching to Game!");
+                    Trans::Switch(Box::new(Pong::new(self.sprite_sheet_handle.clone())))
+                } else if Some(target) == self.watch_replay_button {
+                    match self.replay.take() {
+                        Some(replay) => {
+                            log::info!("[Trans::Switch] Switching to Replay!");
+                            Trans::Switch(Box::new(ReplayState::new(self.sprite_sheet_handle.clone(), replay)))
+                        }
+                        None => watch_last_replay(self.sprite_sheet_handle.clone()),
+                    }
+                } else if Some(target) == self.exit_to_main_menu_button {
+                    log::info!("[Trans::Switch] Switching to MainMenu!");
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else {
+                    Trans::None
+                }
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        let players = data.world.read_resource::<Settings>().players;
+        // once deferred creation of the root ui entity finishes, look up buttons and fill in labels
+        if self.result_lbl.is_none()
+            || self.score_lbl.is_none()
+            || self.rematch_button.is_none()
+            || self.watch_replay_button.is_none()
+            || self.exit_to_main_menu_button.is_none()
+        {
+            data.world.exec(|(ui_finder, mut write): (UiFinder<'_>, WriteStorage<UiText>)| {
+                self.result_lbl = ui_finder.find(RESULT_LBL);
+                self.score_lbl = ui_finder.find(SCORE_LBL);
+                self.rematch_button = ui_finder.find(REMATCH_BUTTON_ID);
+                self.watch_replay_button = ui_finder.find(WATCH_REPLAY_BUTTON_ID);
+                self.exit_to_main_menu_button = ui_finder.find(EXIT_TO_MAIN_MENU_BUTTON_ID);
+                if let Some(result_lbl) = self.result_lbl {
+                    write.get_mut(result_lbl).unwrap().text = result_text(self.result, players).to_string();
+                }
+                if let Some(score_lbl) = self.score_lbl {
+                    write.get_mut(score_lbl).unwrap().text = format_score(&self.score);
+                }
+            });
+        }
+        Trans::None
+    }
+}
+
+fn result_text(result: MatchResult, players: Players) -> &'static str {
+    match (result, players) {
+        (MatchResult::Winner(Side::Left), Players::OnePlayer) => "YOU WIN",
+        (MatchResult::Winner(Side::Right), Players::OnePlayer) => "YOU LOSE",
+        (MatchResult::Winner(Side::Left), Players::TwoPlayers) => "LEFT WINS",
+        (MatchResult::Winner(Side::Right), Players::TwoPlayers) => "RIGHT WINS",
+        (MatchResult::Draw, _) => "DRAW",
+    }
+}
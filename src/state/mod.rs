@@ -3,6 +3,14 @@ pub mod start;
 pub mod pause;
 pub mod main_menu;
 pub mod options;
+pub mod match_over;
+pub mod players;
+pub mod controls;
+pub mod display;
+pub mod replay;
+pub mod high_scores;
+pub mod stats;
+pub mod levels;
 
 pub struct Pause {
     pub(crate) paused: bool,
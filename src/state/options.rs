@@ -3,6 +3,8 @@ use amethyst::{SimpleState, StateData, GameData, StateEvent, SimpleTrans, Trans}
 use amethyst::ui::{UiCreator, UiFinder, UiEvent, UiEventType, UiText};
 use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
 use crate::state::main_menu::MainMenu;
+use crate::state::controls::ControlsState;
+use crate::state::display::DisplayState;
 use amethyst::audio::AudioSink;
 use crate::persistence::{Settings};
 use amethyst::shred::ReadExpect;
@@ -13,6 +15,8 @@ const MUS_DN_BNV: &str = "mus_dn";
 const FX_UP_BTN: &str = "fx_up";
 const FX_LBL: &str = "fx_text";
 const FX_DN_BTN: &str = "fx_dn";
+const DISPLAY_BTN: &str = "display";
+const CONTROLS_BTN: &str = "controls";
 const BACK_BTN: &str = "back";
 
 #[derive(Default)]
@@ -24,10 +28,12 @@ pub struct OptionState {
     fx_dn_btn: Option<Entity>,
     fx_lbl: Option<Entity>,
     fx_up_btn: Option<Entity>,
+    display_btn: Option<Entity>,
+    controls_btn: Option<Entity>,
     back_btn: Option<Entity>,
 }
 
-impl<'a> SimpleState for OptionState {
+impl SimpleState for OptionState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
         self.root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/options.ron", ())))
@@ -45,6 +51,8 @@ impl<'a> SimpleState for OptionState {
         self.fx_lbl = None;
         self.fx_up_btn = None;
         self.fx_dn_btn = None;
+        self.display_btn = None;
+        self.controls_btn = None;
         self.back_btn = None;
     }
 
@@ -69,6 +77,12 @@ impl<'a> SimpleState for OptionState {
                 if self.volume_btn_pushed(Some(target)) {
                     self.update_volume(world, Some(target));
                 }
+                if Some(target) == self.display_btn {
+                    return Trans::Switch(Box::new(DisplayState::default()));
+                }
+                if Some(target) == self.controls_btn {
+                    return Trans::Switch(Box::new(ControlsState::default()));
+                }
                 if Some(target) == self.back_btn {
                     return Trans::Switch(Box::new(MainMenu::default()));
                 }
@@ -86,6 +100,8 @@ impl<'a> SimpleState for OptionState {
         if self.mus_dn_btn.is_none()
             || self.mus_up_btn.is_none()
             || self.mus_lbl.is_none()
+            || self.display_btn.is_none()
+            || self.controls_btn.is_none()
             || self.back_btn.is_none()
             || self.fx_up_btn.is_none()
             || self.fx_lbl.is_none()
@@ -98,6 +114,8 @@ impl<'a> SimpleState for OptionState {
                 self.fx_dn_btn = ui_finder.find(FX_DN_BTN);
                 self.fx_lbl = ui_finder.find(FX_LBL);
                 self.fx_up_btn = ui_finder.find(FX_UP_BTN);
+                self.display_btn = ui_finder.find(DISPLAY_BTN);
+                self.controls_btn = ui_finder.find(CONTROLS_BTN);
                 self.back_btn = ui_finder.find(BACK_BTN);
                 if let Some(mus_lbl) = self.mus_lbl {
                     write.get_mut(mus_lbl).unwrap().text = ((settings.audio_settings.music_volume * 10.0) as u8).to_string();
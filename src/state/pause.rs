@@ -1,13 +1,16 @@
 use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans, TransEvent};
-use amethyst::core::ecs::{Entity, WorldExt};
+use amethyst::core::ecs::{Entity, WorldExt, WriteStorage};
 use amethyst::core::ecs::shrev::EventChannel;
-use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
-use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder};
+use amethyst::input::{is_close_requested, is_key_down, InputEvent, VirtualKeyCode};
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
 
+use crate::menu::PAUSE_ACTION;
+use crate::simulation::Simulation;
 use crate::state::main_menu::MainMenu;
 const RESUME_BUTTON_ID: &str = "resume";
 const EXIT_TO_MAIN_MENU_BUTTON_ID: &str = "exit_to_main_menu";
 const EXIT_BUTTON_ID: &str = "exit";
+const SEED_LABEL_ID: &str = "seed";
 /// Adapted, originally from amethyst/evoli src/states/pause_menu.rs
 #[derive(Default)]
 pub struct PauseMenuState {
@@ -15,6 +18,7 @@ pub struct PauseMenuState {
     resume_button: Option<Entity>,
     exit_to_main_menu_button: Option<Entity>,
     exit_button: Option<Entity>,
+    seed_label: Option<Entity>,
     root: Option<Entity>,
 }
 
@@ -25,7 +29,7 @@ impl PauseMenuState {
 // if the "resume" button is clicked, goto MainGameState
 // if the "exit_to_main_menu" button is clicked, remove the pause and main game states and go to MenuState.
 // if the "exit" button is clicked, quit the program.
-impl<'a> SimpleState for PauseMenuState {
+impl SimpleState for PauseMenuState {
     fn on_start(&mut self, data: StateData<GameData>) {
         let world = data.world;
 
@@ -41,6 +45,8 @@ impl<'a> SimpleState for PauseMenuState {
         }
         self.resume_button = None;
         self.exit_to_main_menu_button = None;
+        self.exit_button = None;
+        self.seed_label = None;
     }
 
     fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
@@ -56,6 +62,10 @@ impl<'a> SimpleState for PauseMenuState {
                     Trans::None
                 }
             }
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == PAUSE_ACTION => {
+                log::info!("[Trans::Pop] Closing Pause Menu!");
+                Trans::Pop
+            }
             StateEvent::Ui(UiEvent {
                                event_type: UiEventType::Click,
                                target,
@@ -94,12 +104,22 @@ impl<'a> SimpleState for PauseMenuState {
         if self.resume_button.is_none()
             || self.exit_to_main_menu_button.is_none()
             || self.exit_button.is_none()
+            || self.seed_label.is_none()
         {
             data.world.exec(|ui_finder: UiFinder<'_>| {
                 self.resume_button = ui_finder.find(RESUME_BUTTON_ID);
                 self.exit_to_main_menu_button = ui_finder.find(EXIT_TO_MAIN_MENU_BUTTON_ID);
                 self.exit_button = ui_finder.find(EXIT_BUTTON_ID);
+                self.seed_label = ui_finder.find(SEED_LABEL_ID);
             });
+            if let Some(seed_label) = self.seed_label {
+                let seed = data.world.read_resource::<Simulation>().seed();
+                data.world.exec(|mut texts: WriteStorage<UiText>| {
+                    if let Some(text) = texts.get_mut(seed_label) {
+                        text.text = format!("SEED {}", seed);
+                    }
+                });
+            }
         }
         Trans::None
     }
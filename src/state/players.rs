@@ -0,0 +1,157 @@
+use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
+use amethyst::assets::Handle;
+use amethyst::core::ecs::{Entity, Read, ReadExpect, WorldExt, WriteStorage};
+use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
+use amethyst::renderer::SpriteSheet;
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
+
+use crate::level::Levels;
+use crate::persistence::Settings;
+use crate::simulation::ball::BallMode;
+use crate::simulation::controller::Players;
+use crate::state::levels::LevelsState;
+use crate::state::main_menu::MainMenu;
+use crate::state::pong::Pong;
+
+const ONE_PLAYER_BTN: &str = "one_player";
+const TWO_PLAYERS_BTN: &str = "two_players";
+const BALL_MODE_BTN: &str = "ball_mode";
+const BALL_MODE_TEXT: &str = "ball_mode_btn_txt";
+const LEVEL_BTN: &str = "level";
+const LEVEL_TEXT: &str = "level_btn_txt";
+const BACK_BTN: &str = "back";
+
+pub struct PlayersState {
+    sprite_sheet_handle: Handle<SpriteSheet>,
+    root: Option<Entity>,
+    one_player_btn: Option<Entity>,
+    two_players_btn: Option<Entity>,
+    ball_mode_btn: Option<Entity>,
+    ball_mode_text: Option<Entity>,
+    level_btn: Option<Entity>,
+    level_text: Option<Entity>,
+    back_btn: Option<Entity>,
+}
+
+impl PlayersState {
+    pub fn new(sprite_sheet_handle: Handle<SpriteSheet>) -> Self {
+        PlayersState {
+            sprite_sheet_handle,
+            root: None,
+            one_player_btn: None,
+            two_players_btn: None,
+            ball_mode_btn: None,
+            ball_mode_text: None,
+            level_btn: None,
+            level_text: None,
+            back_btn: None,
+        }
+    }
+
+    fn start(&self, data: StateData<GameData>, players: Players) -> SimpleTrans {
+        let mut settings = data.world.write_resource::<Settings>();
+        settings.players = players;
+        settings.persist_async();
+        log::info!("[Trans::Switch] Switching to Game with {:?}!", players);
+        Trans::Switch(Box::new(Pong::new(self.sprite_sheet_handle.clone())))
+    }
+}
+
+impl SimpleState for PlayersState {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+        self.root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/players.ron", ())));
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        if let Some(root) = self.root {
+            if data.world.delete_entity(root).is_ok() {
+                self.root = None;
+            }
+        }
+        self.one_player_btn = None;
+        self.two_players_btn = None;
+        self.ball_mode_btn = None;
+        self.ball_mode_text = None;
+        self.level_btn = None;
+        self.level_text = None;
+        self.back_btn = None;
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    log::info!("[Trans::Switch] Switching back to Main menu!");
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else {
+                    Trans::None
+                }
+            }
+            StateEvent::Ui(UiEvent {
+                               event_type: UiEventType::Click,
+                               target,
+                           }) => {
+                if Some(target) == self.one_player_btn {
+                    self.start(data, Players::OnePlayer)
+                } else if Some(target) == self.two_players_btn {
+                    self.start(data, Players::TwoPlayers)
+                } else if Some(target) == self.ball_mode_btn {
+                    let mut settings = data.world.write_resource::<Settings>();
+                    settings.ball_mode = settings.ball_mode.next();
+                    settings.persist_async();
+                    log::info!("Ball mode set to {:?}", settings.ball_mode);
+                    if let Some(text) = self.ball_mode_text {
+                        if let Some(ui_text) = data.world.write_component::<UiText>().get_mut(text) {
+                            ui_text.text = ball_mode_label(settings.ball_mode);
+                        }
+                    }
+                    Trans::None
+                } else if Some(target) == self.level_btn {
+                    log::info!("[Trans::Switch] Switching to Levels!");
+                    Trans::Switch(Box::new(LevelsState::new(self.sprite_sheet_handle.clone())))
+                } else if Some(target) == self.back_btn {
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else {
+                    Trans::None
+                }
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        if self.one_player_btn.is_none()
+            || self.two_players_btn.is_none()
+            || self.ball_mode_btn.is_none()
+            || self.ball_mode_text.is_none()
+            || self.level_btn.is_none()
+            || self.level_text.is_none()
+            || self.back_btn.is_none()
+        {
+            data.world.exec(|(ui_finder, settings, levels, mut write): (UiFinder<'_>, ReadExpect<Settings>, Read<Levels>, WriteStorage<UiText>)| {
+                self.one_player_btn = ui_finder.find(ONE_PLAYER_BTN);
+                self.two_players_btn = ui_finder.find(TWO_PLAYERS_BTN);
+                self.ball_mode_btn = ui_finder.find(BALL_MODE_BTN);
+                self.ball_mode_text = ui_finder.find(BALL_MODE_TEXT);
+                self.level_btn = ui_finder.find(LEVEL_BTN);
+                self.level_text = ui_finder.find(LEVEL_TEXT);
+                self.back_btn = ui_finder.find(BACK_BTN);
+                if let Some(ball_mode_text) = self.ball_mode_text {
+                    write.get_mut(ball_mode_text).unwrap().text = ball_mode_label(settings.ball_mode);
+                }
+                if let Some(level_text) = self.level_text {
+                    write.get_mut(level_text).unwrap().text = format!("LEVEL: {}", levels.get(settings.level.as_deref()).name.to_uppercase());
+                }
+            });
+        }
+        Trans::None
+    }
+}
+
+fn ball_mode_label(ball_mode: BallMode) -> String {
+    format!("BALLS: {}", ball_mode.name())
+}
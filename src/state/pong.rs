@@ -1,37 +1,81 @@
 use amethyst::{GameData, SimpleState, SimpleTrans, StateData, Trans, StateEvent};
 use amethyst::assets::{Handle, Loader};
 use amethyst::core::{Time, Transform};
-use amethyst::core::ecs::{Builder, World, WorldExt, Entity, Join, WriteStorage, ReadStorage};
+use amethyst::core::ecs::{Builder, World, WorldExt, Entity};
 use amethyst::renderer::{Camera, SpriteRender, SpriteSheet};
 use amethyst::ui::{Anchor, LineMode, TtfFormat, UiText, UiTransform};
 
 use crate::ball::component::Ball;
 use crate::paddle::component::{Paddle, Side};
 use crate::timer::TimerText;
-use rand::Rng;
-use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
+use amethyst::input::{is_close_requested, is_key_down, InputEvent, VirtualKeyCode};
 use crate::state::pause::PauseMenuState;
+use crate::menu::PAUSE_ACTION;
 use crate::state::Pause;
-use crate::persistence::window::WindowSettings;
 use amethyst::core::math::Vector3;
 use amethyst::window::ScreenDimensions;
 use crate::persistence::Settings;
-use crate::taunt::TauntComponent;
+use crate::simulation::{Simulation, SimulationConfig, TickInput};
+use crate::simulation::arena::ARENA;
+use crate::simulation::ball::BallId;
+use crate::simulation::power_up::{PowerUpId, PowerUpRules, PowerUpState};
+use crate::power_up::{self, component::PowerUp, power_up_system::PowerUpSystem};
+use crate::level::{self, Levels, component::Obstacle};
+use crate::replay::Replay;
+use crate::sync::PreviousSimulation;
+use crate::score::ScoreText;
+use crate::highscore::{self, HighScore, HighScores, RecordText};
+use crate::stats::Stats;
+use crate::stats::stats_system::StatsSystemDesc;
+use crate::events::GameEvent;
+use amethyst::shrev::{EventChannel, ReaderId};
+use amethyst::core::SystemDesc;
+use crate::score::score_system::format_score;
+use crate::simulation::rules::Score;
+use crate::state::match_over::MatchOverState;
+use crate::ball::ball_system::MoveBallsSystem;
+use crate::ball::trajectory_system::TrajectorySystem;
+use crate::ball::bounce_system::BounceSystem;
+use crate::paddle::paddle::{PaddleSystem, read_tick_input};
+use amethyst::input::{InputHandler, StringBindings};
+use crate::winner::winner::WinnerSystem;
+use amethyst::core::ArcThreadPool;
+use amethyst::core::ecs::{Dispatcher, DispatcherBuilder};
 use amethyst::renderer::rendy::wsi::winit::{Event, WindowEvent};
 
+/// Seconds counted down after leaving the pause menu before the match goes on.
+const RESUME_COUNTDOWN: f32 = 3.0;
 
 pub struct Pong {
-    ball_spawn_timer: Option<f32>,
     sprite_sheet_handle: Handle<SpriteSheet>,
     created_entities: Vec<Entity>,
-    window_settings: WindowSettings,
+    ball_entities: Vec<(BallId, Entity)>,
+    power_up_entities: Vec<(PowerUpId, Entity)>,
     camera: Option<Entity>,
-
+    physics: Option<Dispatcher<'static, 'static>>,
+    /// The replay of the match being played, saved once it stops.
+    recording: Option<Replay>,
+    /// Reads what happens in the match being played, to record rallies and survival times.
+    game_events: Option<ReaderId<GameEvent>>,
+    /// Seconds left until the match goes on after being paused.
+    countdown: Option<f32>,
+    countdown_text: Option<Entity>,
 }
 
 impl Pong {
-    pub fn new(sprite_sheet_handle: Handle<SpriteSheet>, window_settings: WindowSettings) -> Self {
-        Pong { ball_spawn_timer: None, sprite_sheet_handle, created_entities: vec![], window_settings, camera: None }
+    pub fn new(sprite_sheet_handle: Handle<SpriteSheet>) -> Self {
+        Pong {
+            sprite_sheet_handle,
+            created_entities: vec![],
+            ball_entities: vec![],
+            power_up_entities: vec![],
+            camera: None,
+            physics: None,
+            recording: None,
+            game_events: None,
+            countdown: None,
+            countdown_text: None,
+        }
     }
 }
 
@@ -39,49 +83,71 @@ impl SimpleState for Pong {
 
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
-        world.get_mut::<Pause>().unwrap().paused = false;
-
-        self.ball_spawn_timer.replace(2.0);
-        let (left, right) = self.initialise_paddles(world, self.sprite_sheet_handle.clone());
-        self.re_init_camera(world);
-        initialise_timer(world);
-        self.created_entities.push(left);
-        self.created_entities.push(right);
+        let settings = (*world.read_resource::<Settings>()).clone();
+        let config = SimulationConfig {
+            match_rules: settings.match_rules,
+            controllers: settings.players.controllers(settings.difficulty),
+            seed: settings.match_seed(),
+            ball_mode: settings.ball_mode,
+            power_ups: (*world.read_resource::<PowerUpRules>()).clone(),
+            bounce: settings.bounce,
+            level: world.read_resource::<Levels>().get(settings.level.as_deref()).clone(),
+        };
+        log::info!("Starting match with seed {}", config.seed);
+        let fixed_step = world.read_resource::<Time>().fixed_seconds();
+        self.recording = Some(Replay::new(config.clone(), fixed_step));
+        self.start_match(world, config);
+        world.write_resource::<Stats>().start_match();
+        self.game_events = Some(world.write_resource::<EventChannel<GameEvent>>().register_reader());
+        if highscore::tracks(&settings) {
+            let fingerprint = {
+                let simulation = world.read_resource::<Simulation>();
+                highscore::fingerprint(&settings, simulation.power_up_rules(), simulation.level())
+            };
+            let to_beat = world.read_resource::<HighScores>().best(settings.difficulty, &fingerprint);
+            initialise_record_banner(world, to_beat);
+        }
     }
 
     fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
-        data.world.get_mut::<Pause>().unwrap().paused = true;
-        data.world.delete_entities(&self.created_entities).unwrap();
-        if let Some(timer_text) = data.world.get_mut::<TimerText>() {
-            if let Some(timer) = timer_text.timer.take() {
-                if let Err(err) = data.world.delete_entity(timer) {
-                    log::error!("Tried to remove wrong generation entity, err={}", err);
-                }
+        if let Some(recording) = self.recording.take() {
+            if !recording.is_empty() {
+                recording.save_async();
             }
         }
-
+        // Leaving the match ends the current run as well.
+        let survived = data.world.read_resource::<Simulation>().survived();
+        record_survival(data.world, survived);
+        let elapsed = data.world.read_resource::<Simulation>().elapsed();
+        data.world.write_resource::<Stats>().stop_match(elapsed);
+        self.game_events = None;
+        self.stop_match(data.world);
     }
 
     fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
         world.get_mut::<Pause>().unwrap().paused = true;
-
+        // Freeze interpolation on the latest step while no steps are being run.
+        store_previous_simulation(world);
+        self.stop_countdown(world);
     }
 
     fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
-        self.rescale_if_res_updated(world);
-        world.get_mut::<Pause>().unwrap().paused = false;
+        self.resize_if_res_updated(world);
+        // The match stays paused until the countdown is over, so the ball doesn't catch players off guard.
+        self.countdown = Some(RESUME_COUNTDOWN);
+        self.countdown_text = Some(initialise_countdown(world));
     }
 
 
     fn handle_event(&mut self, data: StateData<'_, GameData>, event: StateEvent) -> SimpleTrans {
         match &event {
             StateEvent::Window(event) => {
-                if is_close_requested(&event) {
+                if is_close_requested(event) {
                     log::info!("[Trans::Quit] Quitting Application!");
                     Trans::Quit
-                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                } else if is_key_down(event, VirtualKeyCode::Escape) {
                     log::info!("[Trans::Push] Pausing Game!");
                     pause()
                 } else {
@@ -89,7 +155,11 @@ impl SimpleState for Pong {
                         Event::WindowEvent { ref event, .. } => {
                             match *event {
                                 WindowEvent::Resized(_) => {
-                                    self.rescale_if_res_updated(data.world);
+                                    self.resize_if_res_updated(data.world);
+                                    pause()
+                                },
+                                WindowEvent::Focused(false) => {
+                                    log::info!("[Trans::Push] Window lost focus, pausing Game!");
                                     pause()
                                 },
                                 _ => Trans::None
@@ -101,95 +171,215 @@ impl SimpleState for Pong {
                     }
                 }
             },
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == PAUSE_ACTION => pause(),
+            // Pause rather than let the paddle of an unplugged gamepad drift, plugging it back in
+            // gives it the same controller id again.
+            StateEvent::Input(InputEvent::ControllerDisconnected { which }) => {
+                log::info!("Controller {} disconnected, pausing Game!", which);
+                pause()
+            }
+            StateEvent::Input(InputEvent::ControllerConnected { which }) => {
+                log::info!("Controller {} connected", which);
+                Trans::None
+            }
             _ => Trans::None
         }
     }
 
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
-        if let Some(mut timer) = self.ball_spawn_timer.take() {
-            // If the timer isn't expired yet, subtract the time that passed since the last update.
-            {
-                let time = data.world.fetch::<Time>();
-                timer -= time.delta_seconds();
-            }
-            if timer <= 0.0 {
-                // When timer expire, spawn the ball
-                self.created_entities.push(self.initialise_ball(data.world, self.sprite_sheet_handle.clone()));
+        if let Some(countdown) = self.countdown {
+            let world = &mut data.world;
+            let left = countdown - world.read_resource::<Time>().delta_real_seconds();
+            if left > 0.0 {
+                self.countdown = Some(left);
+                let mut texts = world.write_storage::<UiText>();
+                if let Some(ui_text) = self.countdown_text.and_then(|entity| texts.get_mut(entity)) {
+                    ui_text.text = format_countdown(left);
+                }
             } else {
-                // If timer is not expired yet, put it back onto the state.
-                self.ball_spawn_timer.replace(timer);
+                self.stop_countdown(world);
+                world.get_mut::<Pause>().unwrap().paused = false;
             }
         }
         Trans::None
     }
 
+    fn fixed_update(&mut self, data: StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.countdown.is_some() {
+            // Skipped steps aren't recorded either, so replays play the same as the match.
+            self.hold(data.world);
+            return Trans::None;
+        }
+        let input = read_tick_input(&data.world.read_resource::<InputHandler<StringBindings>>(), &data.world.read_resource::<Simulation>());
+        self.run_tick(data.world, input);
+        if let Some(recording) = self.recording.as_mut() {
+            recording.record(input);
+        }
+        self.read_game_events(data.world);
+        let (result, score) = {
+            let simulation = data.world.read_resource::<Simulation>();
+            (simulation.match_result(), *simulation.score())
+        };
+        if let Some(result) = result {
+            log::info!("[Trans::Switch] Match over, {:?}!", result);
+            return Trans::Switch(Box::new(MatchOverState::new(self.sprite_sheet_handle.clone(), result, score, self.recording.clone())));
+        }
+        Trans::None
+    }
+
 }
 
 impl Pong {
 
-    fn rescale_if_res_updated(&mut self, world: &mut World) {
+    /// Sets up the arena and starts simulating a match from `config`.
+    pub(crate) fn start_match(&mut self, world: &mut World, config: SimulationConfig) {
+        world.get_mut::<Pause>().unwrap().paused = false;
+        world.insert(Simulation::new(config));
+        world.insert(TickInput::default());
+        store_previous_simulation(world);
+        self.physics = Some(build_physics_dispatcher(world));
+        let (left, right) = self.initialise_paddles(world, self.sprite_sheet_handle.clone());
+        let obstacles = self.initialise_obstacles(world, self.sprite_sheet_handle.clone());
+        self.re_init_camera(world);
+        initialise_timer(world);
+        initialise_score(world);
+        self.created_entities.push(left);
+        self.created_entities.push(right);
+        self.created_entities.extend(obstacles);
+    }
+
+    pub(crate) fn stop_match(&mut self, world: &mut World) {
+        world.get_mut::<Pause>().unwrap().paused = true;
+        self.stop_countdown(world);
+        world.delete_entities(&self.created_entities).unwrap();
+        self.created_entities.clear();
+        self.delete_ball_entities(world);
+        self.delete_power_up_entities(world);
+        world.insert(Simulation::default());
+        world.insert(PreviousSimulation::default());
+        self.physics = None;
+        if let Some(timer_text) = world.get_mut::<TimerText>() {
+            if let Some(timer) = timer_text.timer.take() {
+                if let Err(err) = world.delete_entity(timer) {
+                    log::error!("Tried to remove wrong generation entity, err={}", err);
+                }
+            }
+        }
+        if let Some(score_text) = world.get_mut::<ScoreText>() {
+            if let Some(score) = score_text.score.take() {
+                if let Err(err) = world.delete_entity(score) {
+                    log::error!("Tried to remove wrong generation entity, err={}", err);
+                }
+            }
+        }
+        if let Some(record_text) = world.get_mut::<RecordText>() {
+            if let Some(banner) = record_text.banner.take() {
+                if let Err(err) = world.delete_entity(banner) {
+                    log::error!("Tried to remove wrong generation entity, err={}", err);
+                }
+            }
+        }
+        if let Some(camera) = self.camera.take() {
+            if let Err(err) = world.delete_entity(camera) {
+                log::error!("Tried to remove wrong generation entity, err={}", err);
+            }
+        }
+    }
+
+    fn stop_countdown(&mut self, world: &mut World) {
+        self.countdown = None;
+        if let Some(text) = self.countdown_text.take() {
+            if let Err(err) = world.delete_entity(text) {
+                log::error!("Tried to remove wrong generation entity, err={}", err);
+            }
+        }
+    }
+
+    fn read_game_events(&mut self, world: &mut World) {
+        let reader = match self.game_events.as_mut() {
+            Some(reader) => reader,
+            None => return,
+        };
+        let events: Vec<GameEvent> = world.read_resource::<EventChannel<GameEvent>>().read(reader).copied().collect();
+        for event in events {
+            match event {
+                GameEvent::RallyStart => {
+                    if let Some(recording) = self.recording.as_mut() {
+                        recording.record_rally_start();
+                    }
+                }
+                GameEvent::Goal { side: Side::Left } => {
+                    let last_run = world.read_resource::<Simulation>().last_run();
+                    if let Some(survived) = last_run {
+                        record_survival(world, survived);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Runs the physics systems for one fixed step with `input`.
+    pub(crate) fn run_tick(&mut self, world: &mut World, input: TickInput) {
+        store_previous_simulation(world);
+        world.insert(input);
+        if let Some(physics) = self.physics.as_mut() {
+            physics.dispatch(world);
+        }
+        self.sync_ball_entities(world);
+        self.sync_power_up_entities(world);
+    }
+
+    /// Shows the latest step without interpolating, for fixed steps that don't run a tick.
+    pub(crate) fn hold(&self, world: &mut World) {
+        store_previous_simulation(world);
+    }
+
+    /// Replaces the simulation, such as when jumping to another moment of a replay.
+    pub(crate) fn replace_simulation(&mut self, world: &mut World, simulation: Simulation) {
+        world.insert(simulation);
+        store_previous_simulation(world);
+        self.delete_ball_entities(world);
+        self.sync_ball_entities(world);
+        self.delete_power_up_entities(world);
+        self.sync_power_up_entities(world);
+    }
+
+    /// Remembers the new window size and fits the arena into it, the simulation is not affected.
+    fn resize_if_res_updated(&mut self, world: &mut World) {
         let dimensions = world.read_resource::<ScreenDimensions>();
         let mut settings = world.write_resource::<Settings>();
 
-        let old = *settings;
         if settings.update_window(&dimensions) {
-            self.window_settings = settings.window_settings;
-            let other = *settings;
+            let other = settings.clone();
             drop(dimensions);
             drop(settings);
-            self.rescale(world, old);
+            self.re_init_camera(world);
             other.persist_async();
         };
     }
 
-    fn rescale(&mut self, world: &mut World, old_settings: Settings) {
-        world.exec(|(mut paddles, mut balls, taunts, mut transforms): (WriteStorage<Paddle>, WriteStorage<Ball>, ReadStorage<TauntComponent>, WriteStorage<Transform>)| {
-            for (paddle, transform) in (&mut paddles, &mut transforms).join() {
-                transform.set_scale(Vector3::new(self.window_settings.paddle_width_scale(), self.window_settings.paddle_height_scale(), 1.0));
-                let new_y = transform.translation().y * self.window_settings.arena_height() / old_settings.window_settings.arena_height();
-                if paddle.side == Side::Left {
-                    transform.set_translation_xyz(self.window_settings.paddle_width() * 0.5, new_y, 0.0);
-                } else if paddle.side == Side::Right {
-                    transform.set_translation_xyz(self.window_settings.arena_width() - self.window_settings.paddle_width() * 0.5, new_y, 0.0);
-                }
-
-                paddle.width = self.window_settings.paddle_width();
-                paddle.height = self.window_settings.paddle_height();
-            }
-            for (ball, transform) in (&mut balls, &mut transforms).join() {
-                transform.set_scale(Vector3::new(self.window_settings.ball_scale(), self.window_settings.ball_scale(), 1.0));
-                let x = transform.translation().x;
-                let y = transform.translation().y;
-                let new_x = x * self.window_settings.arena_width() / old_settings.window_settings.arena_width();
-                let new_y = y * self.window_settings.arena_height() / old_settings.window_settings.arena_height();
-                transform.set_translation_xyz(new_x, new_y, 0.0);
-                ball.calculated_impact_y = None;
-                ball.radius = self.window_settings.ball_radius();
-                let rel_x = ball.velocity[0] / old_settings.window_settings.arena_width();
-                let rel_y = ball.velocity[1] / old_settings.window_settings.arena_height();
-                ball.velocity[0] = rel_x * self.window_settings.arena_width();
-                ball.velocity[1] = rel_y * self.window_settings.arena_height();
-            }
-            for (_taunt, transform) in (&taunts, &mut transforms).join() {
-                transform.set_translation_xyz(self.window_settings.arena_width() / 2.0, self.window_settings.arena_height() - self.window_settings.taunt_height() / 2.0, -1.0);
-                transform.set_scale(Vector3::new(self.window_settings.taunt_scale(), self.window_settings.taunt_scale(), 1.0));
-            }
-        });
-        self.re_init_camera(world);
-
-    }
-
     fn re_init_camera(&mut self, world: &mut World) {
-        // Setup camera in a way that our screen covers whole arena and (0, 0) is in the bottom left.
+        // Letterbox the arena: the camera shows all of it with (0, 0) in the bottom left, and the
+        // window's extra width or height is left empty on both sides.
+        let (view_width, view_height) = {
+            let dimensions = world.read_resource::<ScreenDimensions>();
+            let aspect = dimensions.aspect_ratio();
+            if aspect > ARENA.width() / ARENA.height() {
+                (ARENA.height() * aspect, ARENA.height())
+            } else {
+                (ARENA.width(), ARENA.width() / aspect)
+            }
+        };
         let mut transform = Transform::default();
-        transform.set_translation_xyz(self.window_settings.arena_width() * 0.5, self.window_settings.arena_height() * 0.5, 1.0);
+        transform.set_translation_xyz(ARENA.width() * 0.5, ARENA.height() * 0.5, 1.0);
 
         if let Some(old) = self.camera {
             world.delete_entity(old).unwrap();
         }
         self.camera = Some(world
             .create_entity()
-            .with(Camera::standard_2d(self.window_settings.arena_width(), self.window_settings.arena_height()))
+            .with(Camera::standard_2d(view_width, view_height))
             .with(transform)
             .build())
     }
@@ -201,52 +391,131 @@ impl Pong {
         let sprite_render = SpriteRender::new(sprite_sheet_handle, 0);
 
         // Correctly position the paddles.
-        let y = self.window_settings.arena_height() / 2.0;
-        left_transform.set_translation_xyz(self.window_settings.paddle_width() * 0.5, y, 0.0);
-        left_transform.set_scale(Vector3::new(self.window_settings.paddle_width_scale(), self.window_settings.paddle_height_scale(), 1.0));
-        right_transform.set_translation_xyz(self.window_settings.arena_width() - self.window_settings.paddle_width() * 0.5, y, 0.0);
-        right_transform.set_scale(Vector3::new(self.window_settings.paddle_width_scale(), self.window_settings.paddle_height_scale(), 1.0));
+        let y = ARENA.height() / 2.0;
+        left_transform.set_translation_xyz(ARENA.paddle_width() * 0.5, y, 0.0);
+        left_transform.set_scale(Vector3::new(ARENA.paddle_width_scale(), ARENA.paddle_height_scale(), 1.0));
+        right_transform.set_translation_xyz(ARENA.width() - ARENA.paddle_width() * 0.5, y, 0.0);
+        right_transform.set_scale(Vector3::new(ARENA.paddle_width_scale(), ARENA.paddle_height_scale(), 1.0));
 
         // Create a left plank entity.
         (world
              .create_entity()
              .with(sprite_render.clone())
-             .with(Paddle::new(Side::Left, self.window_settings.paddle_height(), self.window_settings.paddle_width()))
+             .with(Paddle::new(Side::Left))
              .with(left_transform)
              .build(),
          world
              .create_entity()
              .with(sprite_render)
-             .with(Paddle::new(Side::Right, self.window_settings.paddle_height(), self.window_settings.paddle_width()))
+             .with(Paddle::new(Side::Right))
              .with(right_transform)
              .build())
     }
 
 
 
-    /// Initialises one ball in the middle-ish of the arena.
-    fn initialise_ball(&self, world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) -> Entity {
+    /// Initialises the entities showing the obstacles of the level, the sync system moves them along.
+    fn initialise_obstacles(&self, world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) -> Vec<Entity> {
+        let obstacles = world.read_resource::<Simulation>().obstacles().to_vec();
+        obstacles.iter()
+            .enumerate()
+            .map(|(index, obstacle)| {
+                let center = obstacle.center();
+                let scale = ARENA.obstacle_scale(obstacle.size());
+                let mut local_transform = Transform::default();
+                local_transform.set_translation_xyz(center[0], center[1], 0.0);
+                local_transform.set_scale(Vector3::new(scale[0], scale[1], 1.0));
+                world
+                    .create_entity()
+                    .with(SpriteRender::new(sprite_sheet_handle.clone(), level::sprite_number(obstacle)))
+                    .with(Obstacle { index })
+                    .with(local_transform)
+                    .build()
+            })
+            .collect()
+    }
+
+    /// Creates entities for balls the simulation spawned, and deletes the ones of balls it removed.
+    fn sync_ball_entities(&mut self, world: &mut World) {
+        let ids: Vec<BallId> = world.read_resource::<Simulation>().balls().iter().map(|ball| ball.id).collect();
+        let (kept, removed): (Vec<_>, Vec<_>) = self.ball_entities.drain(..).partition(|(id, _)| ids.contains(id));
+        self.ball_entities = kept;
+        for (_, entity) in removed {
+            if let Err(err) = world.delete_entity(entity) {
+                log::error!("Tried to remove wrong generation entity, err={}", err);
+            }
+        }
+        for id in ids {
+            if self.ball_entities.iter().all(|(known, _)| *known != id) {
+                let entity = self.initialise_ball(world, self.sprite_sheet_handle.clone(), id);
+                self.ball_entities.push((id, entity));
+            }
+        }
+    }
+
+    fn delete_ball_entities(&mut self, world: &mut World) {
+        for (_, entity) in self.ball_entities.drain(..) {
+            if let Err(err) = world.delete_entity(entity) {
+                log::error!("Tried to remove wrong generation entity, err={}", err);
+            }
+        }
+    }
+
+    /// Creates entities for power-ups the simulation spawned, and deletes the ones that were collected or expired.
+    fn sync_power_up_entities(&mut self, world: &mut World) {
+        let power_ups: Vec<PowerUpState> = world.read_resource::<Simulation>().power_ups().to_vec();
+        let (kept, removed): (Vec<_>, Vec<_>) = self.power_up_entities.drain(..)
+            .partition(|(id, _)| power_ups.iter().any(|power_up| power_up.id == *id));
+        self.power_up_entities = kept;
+        for (_, entity) in removed {
+            if let Err(err) = world.delete_entity(entity) {
+                log::error!("Tried to remove wrong generation entity, err={}", err);
+            }
+        }
+        for power_up in power_ups {
+            if self.power_up_entities.iter().all(|(known, _)| *known != power_up.id) {
+                let entity = self.initialise_power_up(world, self.sprite_sheet_handle.clone(), &power_up);
+                self.power_up_entities.push((power_up.id, entity));
+            }
+        }
+    }
+
+    fn delete_power_up_entities(&mut self, world: &mut World) {
+        for (_, entity) in self.power_up_entities.drain(..) {
+            if let Err(err) = world.delete_entity(entity) {
+                log::error!("Tried to remove wrong generation entity, err={}", err);
+            }
+        }
+    }
+
+    /// Initialises the entity showing `power_up`, which stays where it spawned.
+    fn initialise_power_up(&self, world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>, power_up: &PowerUpState) -> Entity {
+        let mut local_transform = Transform::default();
+        local_transform.set_translation_xyz(power_up.position[0], power_up.position[1], 0.0);
+        local_transform.set_scale(Vector3::new(ARENA.power_up_scale(), ARENA.power_up_scale(), 1.0));
+
+        world
+            .create_entity()
+            .with(SpriteRender::new(sprite_sheet_handle, power_up::sprite_number(power_up.kind)))
+            .with(PowerUp { id: power_up.id })
+            .with(local_transform)
+            .build()
+    }
+
+    /// Initialises the entity showing the ball `id`, in the middle-ish of the arena until it is synced.
+    fn initialise_ball(&self, world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>, id: BallId) -> Entity {
         // Create the translation.
         let mut local_transform = Transform::default();
-        local_transform.set_translation_xyz(self.window_settings.arena_width() / 2.0, self.window_settings.arena_height() / 2.0, 0.0);
-        local_transform.set_scale(Vector3::new(self.window_settings.ball_scale(), self.window_settings.ball_scale(), 1.0));
+        local_transform.set_translation_xyz(ARENA.width() / 2.0, ARENA.height() / 2.0, 0.0);
+        local_transform.set_scale(Vector3::new(ARENA.ball_scale(), ARENA.ball_scale(), 1.0));
 
         // Assign the sprite for the ball. The ball is the second sprite in the sheet.
         let sprite_render = SpriteRender::new(sprite_sheet_handle, 1);
 
-        let mut rn = rand::thread_rng();
-        let neg_x = rn.gen_bool(0.5);
-        let neg_y = rn.gen_bool(0.5);
-        let mult_x = if neg_x { 1.0 } else { -1.0 };
-        let mult_y = if neg_y { 1.0 } else { -1.0 };
         world
             .create_entity()
             .with(sprite_render)
-            .with(Ball {
-                radius: self.window_settings.ball_radius(),
-                velocity: [self.window_settings.ball_velocity_x() * mult_x, self.window_settings.ball_velocity_y() * mult_y],
-                calculated_impact_y: None,
-            })
+            .with(Ball { id })
             .with(local_transform)
             .build()
     }
@@ -282,6 +551,144 @@ fn initialise_timer(world: &mut World) {
     world.insert(TimerText { game_time: -2.0, timer: Some(timer) });
 }
 
+/// Initialises the countdown shown in the middle of the screen before the match goes on.
+fn initialise_countdown(world: &mut World) -> Entity {
+    let font = world.read_resource::<Loader>().load(
+        "font/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource(),
+    );
+    let countdown_transform = UiTransform::new(
+        "COUNTDOWN".to_string(), Anchor::Middle, Anchor::Middle,
+        0., 0., 1., 300., 200.,
+    );
+
+    world
+        .create_entity()
+        .with(countdown_transform)
+        .with(UiText::new(
+            font,
+            format_countdown(RESUME_COUNTDOWN),
+            [1., 1., 1., 1.],
+            150.,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
+        .build()
+}
+
+fn format_countdown(seconds_left: f32) -> String {
+    format!("{}", seconds_left.ceil() as u32)
+}
+
+/// Initialises the banner shown under the timer once the player survives longer than `to_beat`.
+fn initialise_record_banner(world: &mut World, to_beat: Option<f32>) {
+    let font = world.read_resource::<Loader>().load(
+        "font/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource(),
+    );
+    let banner_transform = UiTransform::new(
+        "RECORD".to_string(), Anchor::TopMiddle, Anchor::TopMiddle,
+        0., -50., 1., 500., 50.,
+    );
+
+    let banner = world
+        .create_entity()
+        .with(banner_transform)
+        .with(UiText::new(
+            font,
+            "".to_string(),
+            [1.0, 0.65, 0., 1.],
+            50.,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
+        .build();
+
+    world.insert(RecordText { banner: Some(banner), to_beat });
+}
+
+/// Adds a run the player survived for `seconds` to the high scores, when the match is one that counts.
+fn record_survival(world: &mut World, seconds: f32) {
+    let settings = (*world.read_resource::<Settings>()).clone();
+    if !highscore::tracks(&settings) || seconds <= 0.0 {
+        return;
+    }
+    let fingerprint = {
+        let simulation = world.read_resource::<Simulation>();
+        highscore::fingerprint(&settings, simulation.power_up_rules(), simulation.level())
+    };
+    let to_beat = {
+        let mut high_scores = world.write_resource::<HighScores>();
+        let score = HighScore {
+            seconds,
+            date: highscore::today(),
+            difficulty: settings.difficulty,
+            fingerprint: fingerprint.clone(),
+        };
+        if let Some(rank) = high_scores.submit(score) {
+            log::info!("Survived {:.1} seconds, number {} of the high scores", seconds, rank + 1);
+            high_scores.persist_async();
+        }
+        high_scores.best(settings.difficulty, &fingerprint)
+    };
+    world.write_resource::<RecordText>().to_beat = to_beat;
+}
+
+fn store_previous_simulation(world: &mut World) {
+    let previous = (*world.read_resource::<Simulation>()).clone();
+    world.insert(PreviousSimulation(previous));
+}
+
+/// The game rules run on their own dispatcher so they can be stepped at a fixed rate,
+/// independent of how often the rest of the game updates.
+fn build_physics_dispatcher(world: &mut World) -> Dispatcher<'static, 'static> {
+    let mut physics = DispatcherBuilder::new()
+        .with(MoveBallsSystem, "ball_system", &[])
+        .with(PowerUpSystem, "power_up_system", &["ball_system"])
+        .with(TrajectorySystem, "trajectory_system", &["power_up_system"])
+        .with(PaddleSystem, "paddle_system", &["trajectory_system"])
+        .with(BounceSystem, "collision_system", &["paddle_system", "ball_system"])
+        .with(WinnerSystem, "winner_system", &["collision_system"])
+        .with(StatsSystemDesc.build(world), "stats_system", &["winner_system"])
+        .with_pool((*world.read_resource::<ArcThreadPool>()).clone())
+        .build();
+    physics.setup(world);
+    physics
+}
+
+/// Initialises the score next to the timer
+fn initialise_score(world: &mut World) {
+    let font = world.read_resource::<Loader>().load(
+        "font/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource(),
+    );
+    let score_transform = UiTransform::new(
+        "SCORE".to_string(), Anchor::TopLeft, Anchor::TopLeft,
+        200., -50., 1., 200., 50.,
+    );
+
+    let score = world
+        .create_entity()
+        .with(score_transform)
+        .with(UiText::new(
+            font,
+            format_score(&Score::default()),
+            [1., 1., 1., 1.],
+            50.,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
+        .build();
+
+    world.insert(ScoreText { score: Some(score) });
+}
+
 fn pause() -> SimpleTrans {
     Trans::Push(Box::new(PauseMenuState::default()))
 }
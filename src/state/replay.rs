@@ -0,0 +1,213 @@
+use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
+use amethyst::assets::{Handle, Loader};
+use amethyst::core::Time;
+use amethyst::core::ecs::{Builder, Entity, World, WorldExt};
+use amethyst::input::{is_close_requested, is_key_down, InputEvent, VirtualKeyCode};
+use amethyst::renderer::SpriteSheet;
+use amethyst::ui::{Anchor, LineMode, TtfFormat, UiText, UiTransform};
+
+use crate::menu::PAUSE_ACTION;
+use crate::replay::Replay;
+use crate::state::main_menu::MainMenu;
+use crate::state::pong::Pong;
+
+/// Playback speeds, cycled through with Up and Down.
+const SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+const NORMAL_SPEED: usize = 2;
+/// Seconds jumped back or forward with Left and Right.
+const SCRUB_SECONDS: f32 = 5.0;
+
+/// Plays a `Replay` back through the same systems as a live match, with its recorded input.
+///
+/// Space pauses, Left and Right jump back and forward, Page Up and Page Down jump to the previous
+/// and next rally, Up and Down change the speed.
+pub struct ReplayState {
+    pong: Pong,
+    replay: Replay,
+    /// The next tick to run.
+    tick: u32,
+    speed: usize,
+    paused: bool,
+    /// Ticks owed to the playback speed, carried over between fixed steps.
+    pending_ticks: f32,
+    /// The fixed step of the game, which is swapped for the replay's while it plays.
+    game_fixed_step: Option<f32>,
+    status: Option<Entity>,
+}
+
+impl ReplayState {
+    pub fn new(sprite_sheet_handle: Handle<SpriteSheet>, replay: Replay) -> Self {
+        ReplayState {
+            pong: Pong::new(sprite_sheet_handle),
+            replay,
+            tick: 0,
+            speed: NORMAL_SPEED,
+            paused: false,
+            pending_ticks: 0.0,
+            game_fixed_step: None,
+            status: None,
+        }
+    }
+
+    fn scrub(&mut self, world: &mut World, seconds: f32) {
+        let ticks = (seconds / self.replay.fixed_step()) as i64;
+        let target = (self.tick as i64 + ticks).clamp(0, self.replay.len() as i64) as u32;
+        self.jump_to(world, target);
+    }
+
+    fn jump_to(&mut self, world: &mut World, target: u32) {
+        log::info!("Jumping from tick {} to {}", self.tick, target);
+        self.pong.replace_simulation(world, self.replay.simulate(target));
+        self.tick = target;
+        self.pending_ticks = 0.0;
+    }
+
+    fn status_text(&self) -> String {
+        let seconds = self.tick as f32 * self.replay.fixed_step();
+        let total = self.replay.len() as f32 * self.replay.fixed_step();
+        let state = if self.tick >= self.replay.len() {
+            "END".to_string()
+        } else if self.paused {
+            "PAUSED".to_string()
+        } else {
+            format!("{}x", SPEEDS[self.speed])
+        };
+        format!("REPLAY {:.1} / {:.1} {}", seconds, total, state)
+    }
+}
+
+impl SimpleState for ReplayState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        {
+            let mut time = world.write_resource::<Time>();
+            self.game_fixed_step = Some(time.fixed_seconds());
+            time.set_fixed_seconds(self.replay.fixed_step());
+        }
+        self.pong.start_match(world, self.replay.config().clone());
+        self.status = Some(initialise_status(world));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        self.pong.stop_match(world);
+        if let Some(fixed_step) = self.game_fixed_step.take() {
+            world.write_resource::<Time>().set_fixed_seconds(fixed_step);
+        }
+        if let Some(status) = self.status.take() {
+            if let Err(err) = world.delete_entity(status) {
+                log::error!("Tried to remove wrong generation entity, err={}", err);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match &event {
+            StateEvent::Window(event) => {
+                if is_close_requested(event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(event, VirtualKeyCode::Escape) {
+                    log::info!("[Trans::Switch] Switching to MainMenu!");
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else {
+                    if is_key_down(event, VirtualKeyCode::Space) {
+                        self.paused = !self.paused;
+                    } else if is_key_down(event, VirtualKeyCode::Left) {
+                        self.scrub(data.world, -SCRUB_SECONDS);
+                    } else if is_key_down(event, VirtualKeyCode::Right) {
+                        self.scrub(data.world, SCRUB_SECONDS);
+                    } else if is_key_down(event, VirtualKeyCode::PageUp) {
+                        if let Some(start) = self.replay.previous_rally(self.tick) {
+                            self.jump_to(data.world, start);
+                        }
+                    } else if is_key_down(event, VirtualKeyCode::PageDown) {
+                        if let Some(start) = self.replay.next_rally(self.tick) {
+                            self.jump_to(data.world, start);
+                        }
+                    } else if is_key_down(event, VirtualKeyCode::Up) {
+                        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+                    } else if is_key_down(event, VirtualKeyCode::Down) {
+                        self.speed = self.speed.saturating_sub(1);
+                    }
+                    Trans::None
+                }
+            }
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == PAUSE_ACTION => {
+                self.paused = !self.paused;
+                Trans::None
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn fixed_update(&mut self, data: StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = data.world;
+        if !self.paused {
+            self.pending_ticks += SPEEDS[self.speed];
+        }
+        if self.pending_ticks < 1.0 {
+            self.pong.hold(world);
+        }
+        while self.pending_ticks >= 1.0 {
+            self.pending_ticks -= 1.0;
+            let input = match self.replay.input(self.tick) {
+                Some(input) => input,
+                None => {
+                    self.pending_ticks = 0.0;
+                    self.pong.hold(world);
+                    break;
+                }
+            };
+            self.pong.run_tick(world, input);
+            self.tick += 1;
+        }
+
+        if let Some(status) = self.status {
+            if let Some(ui_text) = world.write_component::<UiText>().get_mut(status) {
+                ui_text.text = self.status_text();
+            }
+        }
+        Trans::None
+    }
+}
+
+/// Shows where in the replay playback is, in the top right corner.
+fn initialise_status(world: &mut World) -> Entity {
+    let font = world.read_resource::<Loader>().load(
+        "font/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource(),
+    );
+    let status_transform = UiTransform::new(
+        "REPLAY".to_string(), Anchor::TopRight, Anchor::TopRight,
+        0., -50., 1., 700., 50.,
+    );
+    world
+        .create_entity()
+        .with(status_transform)
+        .with(UiText::new(
+            font,
+            String::new(),
+            [1., 0.65, 0., 1.],
+            50.,
+            LineMode::Single,
+            Anchor::MiddleRight,
+        ))
+        .build()
+}
+
+/// Loads the last replay and plays it, or logs why it can't be played.
+pub fn watch_last_replay(sprite_sheet_handle: Handle<SpriteSheet>) -> SimpleTrans {
+    match Replay::load_last() {
+        Ok(replay) => {
+            log::info!("[Trans::Switch] Switching to Replay!");
+            Trans::Switch(Box::new(ReplayState::new(sprite_sheet_handle, replay)))
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            Trans::None
+        }
+    }
+}
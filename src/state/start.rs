@@ -1,24 +1,48 @@
 use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
 use amethyst::core::ecs::{Entity, WorldExt};
-use amethyst::input::{is_close_requested, is_key_down, is_mouse_button_down, VirtualKeyCode};
+use amethyst::input::{is_close_requested, is_key_down, is_mouse_button_down, InputEvent, VirtualKeyCode};
 use amethyst::renderer::rendy::wsi::winit::MouseButton;
 use amethyst::ui::UiCreator;
 
 use crate::audio::audio::initialise_audio;
+use crate::display::apply_display_settings;
+use crate::menu::CONFIRM_ACTION;
 use crate::state::Pause;
 use crate::taunt::Taunt;
 use crate::timer::TimerText;
+use crate::highscore::{HighScores, RecordText};
+use crate::stats::Stats;
+use crate::power_up;
+use crate::level::Levels;
+use crate::simulation::power_up::PowerUpRules;
 use crate::persistence::Settings;
+use crate::replay::Replay;
+use crate::state::main_menu::{load_sprite_sheet, MainMenu};
+use crate::state::replay::ReplayState;
+
+/// Where the game goes once it is set up.
+#[derive(Debug)]
+pub enum Launch {
+    /// Waits on the start screen for a click.
+    Intro,
+    MainMenu,
+    Replay(Box<Replay>),
+}
 
 #[derive(Debug)]
 pub struct StartScreen {
     settings: Settings,
+    launch: Option<Launch>,
     ui_handle: Option<Entity>,
 }
 
 impl StartScreen {
     pub fn new(settings: Settings) -> Self {
-        StartScreen { settings, ui_handle: None }
+        StartScreen::launching(settings, Launch::Intro)
+    }
+
+    pub fn launching(settings: Settings, launch: Launch) -> Self {
+        StartScreen { settings, launch: Some(launch), ui_handle: None }
     }
 }
 
@@ -28,12 +52,21 @@ impl SimpleState for StartScreen {
         let world = data.world;
         world.insert(Taunt::default());
         world.insert(TimerText::default());
+        world.insert(RecordText::default());
+        // Read once, coming back to the start screen keeps the session going.
+        world.entry::<HighScores>().or_insert_with(HighScores::read_or_default);
+        world.entry::<Stats>().or_insert_with(Stats::read_or_default);
+        world.entry::<PowerUpRules>().or_insert_with(power_up::read_rules);
+        world.entry::<Levels>().or_insert_with(Levels::read);
         world.insert(Pause::default());
-        world.insert(self.settings);
+        world.insert(self.settings.clone());
 
         initialise_audio(world, &self.settings);
-        self.ui_handle =
-            Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/start.ron", ())));
+        apply_display_settings(world, &self.settings.display_settings);
+        if let Some(Launch::Intro) = self.launch {
+            self.ui_handle =
+                Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/start.ron", ())));
+        }
     }
 
     fn on_stop(&mut self, data: StateData<GameData>) {
@@ -49,17 +82,36 @@ impl SimpleState for StartScreen {
     fn handle_event(&mut self, _: StateData<'_, GameData>, event: StateEvent) -> SimpleTrans {
         match &event {
             StateEvent::Window(event) => {
-                if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                if is_close_requested(event) || is_key_down(event, VirtualKeyCode::Escape) {
                     log::info!("[Trans::Quit] Quitting Application!");
                     Trans::Quit
-                } else if is_mouse_button_down(&event, MouseButton::Left) {
+                } else if is_mouse_button_down(event, MouseButton::Left) {
                     log::info!("[Trans::Switch] Switching to MainMenu!");
-                    Trans::Switch(Box::new(crate::state::main_menu::MainMenu::default()))
+                    Trans::Switch(Box::new(MainMenu::default()))
                 } else {
                     Trans::None
                 }
             }
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == CONFIRM_ACTION => {
+                log::info!("[Trans::Switch] Switching to MainMenu!");
+                Trans::Switch(Box::new(MainMenu::default()))
+            }
             _ => Trans::None,
         }
     }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData>) -> SimpleTrans {
+        match self.launch.take() {
+            Some(Launch::MainMenu) => {
+                log::info!("[Trans::Switch] Skipping intro, switching to MainMenu!");
+                Trans::Switch(Box::new(MainMenu::default()))
+            }
+            Some(Launch::Replay(replay)) => {
+                log::info!("[Trans::Switch] Switching to Replay!");
+                let sprite_sheet = load_sprite_sheet(data.world);
+                Trans::Switch(Box::new(ReplayState::new(sprite_sheet, *replay)))
+            }
+            Some(Launch::Intro) | None => Trans::None,
+        }
+    }
 }
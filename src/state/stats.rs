@@ -0,0 +1,101 @@
+use amethyst::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans};
+use amethyst::core::ecs::{Entity, WorldExt};
+use amethyst::input::{is_close_requested, is_key_down, VirtualKeyCode};
+use amethyst::ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText};
+
+use crate::simulation::paddle::Side;
+use crate::state::main_menu::MainMenu;
+use crate::stats::{StatTotals, Stats};
+
+const SESSION_LABEL: &str = "session";
+const LIFETIME_LABEL: &str = "lifetime";
+const BACK_BTN: &str = "back";
+
+/// Rally statistics of this session next to the ones of every session.
+#[derive(Default)]
+pub struct StatsState {
+    root: Option<Entity>,
+    session_label: Option<Entity>,
+    lifetime_label: Option<Entity>,
+    back_btn: Option<Entity>,
+}
+
+impl SimpleState for StatsState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        self.root = Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/stats.ron", ())));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(root) = self.root {
+            if data.world.delete_entity(root).is_ok() {
+                self.root = None;
+            }
+        }
+        self.session_label = None;
+        self.lifetime_label = None;
+        self.back_btn = None;
+    }
+
+    fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) {
+                    log::info!("[Trans::Quit] Quitting Application!");
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    log::info!("[Trans::Switch] Switching back to MainMenu!");
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else {
+                    Trans::None
+                }
+            }
+            StateEvent::Ui(UiEvent {
+                               event_type: UiEventType::Click,
+                               target,
+                           }) if Some(target) == self.back_btn => {
+                log::info!("[Trans::Switch] Switching back to MainMenu!");
+                Trans::Switch(Box::new(MainMenu::default()))
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+        if self.session_label.is_none() || self.lifetime_label.is_none() || self.back_btn.is_none() {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                self.session_label = ui_finder.find(SESSION_LABEL);
+                self.lifetime_label = ui_finder.find(LIFETIME_LABEL);
+                self.back_btn = ui_finder.find(BACK_BTN);
+            });
+            let (session, lifetime) = {
+                let stats = world.read_resource::<Stats>();
+                (stats.session, stats.lifetime)
+            };
+            let mut texts = world.write_storage::<UiText>();
+            for (label, title, totals) in [(self.session_label, "THIS SESSION", session), (self.lifetime_label, "ALL TIME", lifetime)] {
+                if let Some(ui_text) = label.and_then(|label| texts.get_mut(label)) {
+                    ui_text.text = stats_text(title, &totals);
+                }
+            }
+        }
+        Trans::None
+    }
+}
+
+fn stats_text(title: &str, totals: &StatTotals) -> String {
+    let left = Side::Left.index();
+    let right = Side::Right.index();
+    [
+        title.to_string(),
+        String::new(),
+        format!("RALLIES: {}", totals.rallies),
+        format!("HITS: {} LEFT, {} RIGHT", totals.hits[left], totals.hits[right]),
+        format!("LONGEST RALLY: {} HITS", totals.longest_rally),
+        format!("PEAK SPEED: {:.0}% OF MAX", totals.peak_speed * 100.0),
+        format!("TIME SURVIVED: {:.1}s", totals.time_survived),
+        format!("LONGEST SURVIVAL: {:.1}s", totals.longest_survival),
+        format!("CONCEDED: {} LEFT, {} RIGHT", totals.goals_conceded[left], totals.goals_conceded[right]),
+    ].join("\n")
+}
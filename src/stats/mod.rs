@@ -0,0 +1,118 @@
+pub mod stats_system;
+
+use crate::persistence::paths;
+use crate::simulation::paddle::Side;
+
+const STATS_FILE: &str = "stats.ron";
+
+/// Totals over many rallies.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatTotals {
+    /// Rallies played until a goal.
+    pub rallies: u32,
+    /// Paddle hits of the left and the right paddle.
+    pub hits: [u32; 2],
+    /// Most paddle hits in one rally.
+    pub longest_rally: u32,
+    /// Fastest the ball went, as a fraction of `Arena::max_velocity`.
+    pub peak_speed: f32,
+    /// Seconds the ball was in play.
+    pub time_survived: f32,
+    pub longest_survival: f32,
+    /// Goals let in by the left and the right paddle.
+    pub goals_conceded: [u32; 2],
+}
+
+impl StatTotals {
+    fn add(&mut self, rally: &Rally, seconds: f32, conceded_by: Option<Side>) {
+        for side in [Side::Left, Side::Right] {
+            self.hits[side.index()] += rally.hits[side.index()];
+        }
+        self.peak_speed = self.peak_speed.max(rally.peak_speed);
+        self.time_survived += seconds;
+        if let Some(side) = conceded_by {
+            self.rallies += 1;
+            self.goals_conceded[side.index()] += 1;
+            self.longest_rally = self.longest_rally.max(rally.hits.iter().sum());
+            self.longest_survival = self.longest_survival.max(seconds);
+        }
+    }
+}
+
+/// The rally being played.
+#[derive(Debug, Copy, Clone, Default)]
+struct Rally {
+    /// Simulated seconds into the match when the rally was served.
+    served_at: f32,
+    hits: [u32; 2],
+    peak_speed: f32,
+}
+
+/// Statistics of the rallies played since the game started and ever.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub session: StatTotals,
+    pub lifetime: StatTotals,
+    /// Whether a match is being played, replays aren't counted.
+    tracking: bool,
+    /// `None` while no ball is in play.
+    rally: Option<Rally>,
+}
+
+impl Stats {
+    /// Reads the saved lifetime statistics, the session starts from zero.
+    pub fn read_or_default() -> Stats {
+        let lifetime = paths::read_ron_or_default(&paths::user_config_dir().join(STATS_FILE), "statistics");
+        Stats { lifetime, ..Stats::default() }
+    }
+
+    /// Starts counting the rallies of a match.
+    pub fn start_match(&mut self) {
+        self.tracking = true;
+        self.rally = None;
+    }
+
+    /// Starts a rally `elapsed` seconds into the match.
+    pub fn rally_start(&mut self, elapsed: f32) {
+        if self.tracking {
+            self.rally = Some(Rally { served_at: elapsed, ..Rally::default() });
+        }
+    }
+
+    /// `speed` is the horizontal speed of the ball after the hit, as a fraction of `Arena::max_velocity`.
+    pub fn paddle_hit(&mut self, side: Side, speed: f32) {
+        if let Some(rally) = self.rally.as_mut() {
+            rally.hits[side.index()] += 1;
+            rally.peak_speed = rally.peak_speed.max(speed);
+        }
+    }
+
+    /// Ends the rally with a goal behind the paddle on `side`, `elapsed` seconds into the match.
+    pub fn goal(&mut self, side: Side, elapsed: f32) {
+        if let Some(rally) = self.rally.take() {
+            self.add(&rally, elapsed, Some(side));
+        }
+    }
+
+    /// Stops counting when the match stops, the rally in play only adds its time and hits.
+    pub fn stop_match(&mut self, elapsed: f32) {
+        if let Some(rally) = self.rally.take() {
+            self.add(&rally, elapsed, None);
+        }
+        if self.tracking {
+            self.tracking = false;
+            self.persist_async();
+        }
+    }
+
+    fn add(&mut self, rally: &Rally, elapsed: f32, conceded_by: Option<Side>) {
+        let seconds = (elapsed - rally.served_at).max(0.0);
+        self.session.add(rally, seconds, conceded_by);
+        self.lifetime.add(rally, seconds, conceded_by);
+    }
+
+    pub fn persist_async(&self) {
+        paths::persist_ron_async(paths::user_config_dir().join(STATS_FILE), "statistics", self.lifetime);
+    }
+}
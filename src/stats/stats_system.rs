@@ -0,0 +1,42 @@
+use amethyst::core::ecs::{Read, System, SystemData, World, Write};
+use amethyst::core::SystemDesc;
+use amethyst::shrev::{EventChannel, ReaderId};
+
+use crate::events::GameEvent;
+use crate::simulation::Simulation;
+use crate::stats::Stats;
+
+/// Counts the rallies of a match, it runs with the physics so goals are timed on the tick they happen.
+pub struct StatsSystem {
+    reader: ReaderId<GameEvent>,
+}
+
+#[derive(Default, Debug)]
+pub struct StatsSystemDesc;
+
+impl<'a, 'b> SystemDesc<'a, 'b, StatsSystem> for StatsSystemDesc {
+    fn build(self, world: &mut World) -> StatsSystem {
+        <StatsSystem as System<'_>>::SystemData::setup(world);
+        let reader = world.fetch_mut::<EventChannel<GameEvent>>().register_reader();
+        StatsSystem { reader }
+    }
+}
+
+impl<'s> System<'s> for StatsSystem {
+    type SystemData = (
+        Read<'s, EventChannel<GameEvent>>,
+        Read<'s, Simulation>,
+        Write<'s, Stats>,
+    );
+
+    fn run(&mut self, (events, simulation, mut stats): Self::SystemData) {
+        for event in events.read(&mut self.reader) {
+            match *event {
+                GameEvent::RallyStart => stats.rally_start(simulation.elapsed()),
+                GameEvent::PaddleHit { side, speed } => stats.paddle_hit(side, speed / simulation.arena().max_velocity()),
+                GameEvent::Goal { side } => stats.goal(side, simulation.elapsed()),
+                _ => {}
+            }
+        }
+    }
+}
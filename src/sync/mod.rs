@@ -0,0 +1,7 @@
+pub mod sync_system;
+
+use crate::simulation::Simulation;
+
+/// The `Simulation` as it was before the latest fixed step, transforms are interpolated from it.
+#[derive(Default)]
+pub struct PreviousSimulation(pub Simulation);
@@ -0,0 +1,64 @@
+use amethyst::{
+    core::{math::Vector3, Time, Transform},
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
+};
+
+use crate::ball::component::Ball;
+use crate::level::component::Obstacle;
+use crate::paddle::component::Paddle;
+use crate::simulation::Simulation;
+use crate::sync::PreviousSimulation;
+
+/// Places ball, paddle and obstacle transforms, and sizes paddles, between the last two physics steps, so movement stays
+/// smooth when the frame rate doesn't match the fixed step.
+#[derive(SystemDesc)]
+pub struct TransformSyncSystem;
+
+impl<'s> System<'s> for TransformSyncSystem {
+    type SystemData = (
+        ReadStorage<'s, Ball>,
+        ReadStorage<'s, Paddle>,
+        ReadStorage<'s, Obstacle>,
+        WriteStorage<'s, Transform>,
+        Read<'s, Simulation>,
+        Read<'s, PreviousSimulation>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (balls, paddles, obstacles, mut transforms, simulation, previous, time): Self::SystemData) {
+        let alpha = time.interpolation_alpha();
+        for (ball, transform) in (&balls, &mut transforms).join() {
+            if let Some(state) = simulation.ball(ball.id) {
+                let (x, y) = match previous.0.ball(ball.id) {
+                    Some(prev) if !state.teleported => (
+                        lerp(prev.position[0], state.position[0], alpha),
+                        lerp(prev.position[1], state.position[1], alpha),
+                    ),
+                    _ => (state.position[0], state.position[1]),
+                };
+                transform.set_translation_x(x);
+                transform.set_translation_y(y);
+            }
+        }
+        for (paddle, transform) in (&paddles, &mut transforms).join() {
+            let state = simulation.paddle(paddle.side);
+            let prev = previous.0.paddle(paddle.side);
+            transform.set_translation_x(state.x);
+            transform.set_translation_y(lerp(prev.y, state.y, alpha));
+            let scale = transform.scale().x;
+            transform.set_scale(Vector3::new(scale, state.height / simulation.arena().paddle_height() * simulation.arena().paddle_height_scale(), 1.0));
+        }
+        for (obstacle, transform) in (&obstacles, &mut transforms).join() {
+            if let (Some(state), Some(prev)) = (simulation.obstacles().get(obstacle.index), previous.0.obstacles().get(obstacle.index)) {
+                let (center, prev_center) = (state.center(), prev.center());
+                transform.set_translation_x(lerp(prev_center[0], center[0], alpha));
+                transform.set_translation_y(lerp(prev_center[1], center[1], alpha));
+            }
+        }
+    }
+}
+
+fn lerp(from: f32, to: f32, alpha: f32) -> f32 {
+    from + (to - from) * alpha
+}
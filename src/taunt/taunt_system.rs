@@ -1,15 +1,40 @@
-use amethyst::core::{Transform};
-use amethyst::derive::SystemDesc;
+use amethyst::core::{Time, Transform};
+use amethyst::core::ecs::{Read, World};
+use amethyst::core::SystemDesc;
 use amethyst::ecs::{ReadStorage, System, SystemData, WriteStorage, WriteExpect};
+use amethyst::shrev::{EventChannel, ReaderId};
 
 use crate::ball::component::Ball;
+use crate::events::GameEvent;
+use crate::simulation::paddle::Side;
 use crate::taunt::Taunt;
-use amethyst::core::ecs::{Join, Read};
+use amethyst::core::ecs::Join;
 use amethyst::renderer::SpriteRender;
-use crate::persistence::Settings;
+use crate::simulation::arena::ARENA;
 
-#[derive(SystemDesc)]
-pub struct TauntSystem;
+const LOOKING_LEFT: usize = 2;
+const LOOKING_RIGHT: usize = 3;
+const LOOKING_AHEAD: usize = 4;
+/// Seconds the face keeps staring at the goal the ball went in.
+const GOAL_STARE: f32 = 1.0;
+
+/// The face follows the ball with its eyes, and stares at the goal after every goal.
+pub struct TauntSystem {
+    reader: ReaderId<GameEvent>,
+    /// The goal stared at, with the seconds left to stare.
+    staring_at: Option<(Side, f32)>,
+}
+
+#[derive(Default, Debug)]
+pub struct TauntSystemDesc;
+
+impl<'a, 'b> SystemDesc<'a, 'b, TauntSystem> for TauntSystemDesc {
+    fn build(self, world: &mut World) -> TauntSystem {
+        <TauntSystem as System<'_>>::SystemData::setup(world);
+        let reader = world.fetch_mut::<EventChannel<GameEvent>>().register_reader();
+        TauntSystem { reader, staring_at: None }
+    }
+}
 
 impl<'s> System<'s> for TauntSystem {
     type SystemData = (
@@ -17,23 +42,41 @@ impl<'s> System<'s> for TauntSystem {
         ReadStorage<'s, Transform>,
         WriteExpect<'s, Taunt>,
         WriteStorage<'s, SpriteRender>,
-        Read<'s, Settings>,
+        Read<'s, EventChannel<GameEvent>>,
+        Read<'s, Time>,
     );
 
-    fn run(&mut self, (balls, trans, taunt, mut sprites, settings): Self::SystemData) {
+    fn run(&mut self, (balls, trans, taunt, mut sprites, events, time): Self::SystemData) {
+        for event in events.read(&mut self.reader) {
+            if let GameEvent::Goal { side } = *event {
+                self.staring_at = Some((side, GOAL_STARE));
+            }
+        }
+        let face = match taunt.face.and_then(|face| sprites.get_mut(face)) {
+            Some(face) => face,
+            None => return,
+        };
+        if let Some((side, left)) = self.staring_at {
+            let left = left - time.delta_seconds();
+            self.staring_at = if left > 0.0 { Some((side, left)) } else { None };
+            face.sprite_number = match side {
+                Side::Left => LOOKING_LEFT,
+                Side::Right => LOOKING_RIGHT,
+            };
+            return;
+        }
         for (_ball, transform) in (&balls, &trans).join() {
-            let md = settings.window_settings.arena_width() / 3.0;
+            let md = ARENA.width() / 3.0;
             let pos = transform.translation().x / md;
-            let sprite = sprites.get_mut(taunt.face.unwrap()).unwrap();
             let sprite_ind = if pos < 1.0 {
-                2
+                LOOKING_LEFT
             } else if pos < 2.0 {
-                4
+                LOOKING_AHEAD
             } else {
-                3
+                LOOKING_RIGHT
             };
-            if sprite.sprite_number != sprite_ind {
-                sprite.sprite_number = sprite_ind;
+            if face.sprite_number != sprite_ind {
+                face.sprite_number = sprite_ind;
             }
 
         }
@@ -1,17 +1,28 @@
 use amethyst::core::Time;
 use amethyst::core::ecs::{WriteStorage, System, Read, WriteExpect, World};
 use amethyst::ui::UiText;
+use amethyst::shrev::{EventChannel, ReaderId};
 use crate::timer::TimerText;
 use amethyst::prelude::SystemDesc;
 use amethyst::core::ecs::shred::SystemData;
+use crate::events::GameEvent;
+use crate::simulation::Simulation;
+use crate::simulation::paddle::Side;
+use crate::simulation::rules::MatchRules;
 use crate::state::Pause;
 
-pub struct TimerSystem;
+pub struct TimerSystem {
+    reader: ReaderId<GameEvent>,
+}
+
+#[derive(Default, Debug)]
+pub struct TimerSystemDesc;
 
-impl<'a, 'b> SystemDesc<'a, 'b, TimerSystem> for TimerSystem {
+impl<'a, 'b> SystemDesc<'a, 'b, TimerSystem> for TimerSystemDesc {
     fn build(self, world: &mut World) -> TimerSystem {
         <TimerSystem as System>::SystemData::setup(world);
-        TimerSystem
+        let reader = world.fetch_mut::<EventChannel<GameEvent>>().register_reader();
+        TimerSystem { reader }
     }
 }
 
@@ -21,9 +32,20 @@ impl<'s> System<'s> for TimerSystem {
         WriteExpect<'s, TimerText>,
         WriteStorage<'s, UiText>,
         Read<'s, Pause>,
+        Read<'s, EventChannel<GameEvent>>,
+        Read<'s, Simulation>,
     );
 
-    fn run(&mut self, (time, mut timer_text, mut ui_text, pause): Self::SystemData) {
+    fn run(&mut self, (time, mut timer_text, mut ui_text, pause, events, simulation): Self::SystemData) {
+        for event in events.read(&mut self.reader) {
+            // Survival time starts over whenever the player concedes.
+            if *event == (GameEvent::Goal { side: Side::Left }) && *simulation.rules() == MatchRules::Survival {
+                timer_text.game_time = 0.0;
+                if let Some(ui) = timer_text.timer.and_then(|timer| ui_text.get_mut(timer)) {
+                    ui.text = "0.0".to_string();
+                }
+            }
+        }
         if pause.paused {
             return;
         }
@@ -1,77 +1,25 @@
-use amethyst::{
-    core::transform::Transform,
-    derive::SystemDesc,
-    ecs::{Join, System, SystemData, WriteStorage},
-};
-use crate::ball::component::Ball;
-use amethyst::core::ecs::{ReadExpect, Read, WriteExpect};
-use amethyst::assets::{AssetStorage};
-use crate::audio::audio::{Sounds, play_score_sound};
-use amethyst::audio::output::Output;
-use amethyst::audio::Source;
-use crate::timer::TimerText;
-use amethyst::ui::UiText;
+use amethyst::derive::SystemDesc;
+use amethyst::ecs::{System, SystemData};
+use amethyst::core::ecs::{ReadExpect, Write};
+use amethyst::shrev::EventChannel;
+use crate::events::{publish, GameEvent};
 use crate::state::Pause;
-use crate::persistence::{Settings};
+use crate::simulation::Simulation;
 
 #[derive(SystemDesc)]
 pub struct WinnerSystem;
 
 impl<'s> System<'s> for WinnerSystem {
     type SystemData = (
-        WriteStorage<'s, Ball>,
-        WriteStorage<'s, Transform>,
-        Read<'s, AssetStorage<Source>>,
-        ReadExpect<'s, Sounds>,
-        Option<Read<'s, Output>>,
-        WriteExpect<'s, TimerText>,
-        WriteStorage<'s, UiText>,
+        Write<'s, Simulation>,
+        Write<'s, EventChannel<GameEvent>>,
         ReadExpect<'s, Pause>,
-        Read<'s, Settings>,
     );
 
-    fn run(&mut self, (
-        mut balls,
-        mut locals,
-        storage,
-        sounds,
-        audio_output,
-        mut timer_text,
-        mut ui_text,
-        pause,
-        settings
-    ): Self::SystemData) {
+    fn run(&mut self, (mut simulation, mut game_events, pause): Self::SystemData) {
         if pause.paused {
             return;
         }
-        let window_settings = settings.window_settings;
-        for (ball, transform) in (&mut balls, &mut locals).join() {
-            let ball_x = transform.translation().x;
-
-            let did_hit = if ball_x <= ball.radius {
-                // Computer scores.
-                true
-            } else if ball_x >= window_settings.arena_width() - ball.radius {
-                panic!("You broke my game.");
-            } else {
-                false
-            };
-
-            if did_hit {
-                if ball.velocity[0].is_sign_positive() {
-                    ball.velocity[0] = - window_settings.ball_velocity_x();
-                } else {
-                    ball.velocity[0] = window_settings.ball_velocity_x();
-                }
-                ball.velocity[1] = window_settings.ball_velocity_y();
-                transform.set_translation_x(window_settings.arena_width() / 2.0); // Reset Position
-                transform.set_translation_y(window_settings.arena_height() / 2.0); // Reset Position
-                ball.calculated_impact_y = None;
-                play_score_sound(&settings.audio_settings, &*sounds, &storage, audio_output.as_deref());
-                let text = ui_text.get_mut(timer_text.timer.unwrap()).unwrap();
-                timer_text.game_time = 0.0;
-                text.text = format!("0.0");
-            }
-        }
+        publish(&mut game_events, simulation.check_goals());
     }
 }